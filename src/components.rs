@@ -1,4 +1,7 @@
+use crate::resources::AsteroidTuning;
+use crate::shapes::polygon_area;
 use bevy::prelude::*;
+use serde::Deserialize;
 
 // Marker Components - tags basically
 #[derive(Component)]
@@ -7,6 +10,10 @@ pub struct Player;
 #[derive(Component)]
 pub struct Asteroid;
 
+/// An asteroid's outline in its own local space, counter-clockwise
+#[derive(Component, Debug, Clone)]
+pub struct AsteroidShape(pub Vec<Vec2>);
+
 #[derive(Component)]
 pub struct Camera;
 
@@ -14,80 +21,259 @@ pub struct Camera;
 #[derive(Component)]
 pub struct Health {
     current: f32,
+    max: f32,
 }
 
 impl Health {
     pub fn new(max: f32) -> Self {
-        Self { current: max }
+        Self { current: max, max }
     }
 
     pub fn current(&self) -> f32 {
         self.current
     }
 
+    pub fn max(&self) -> f32 {
+        self.max
+    }
+
+    /// Fraction of health left, 0.0 to 1.0
+    pub fn fraction(&self) -> f32 {
+        self.current / self.max
+    }
+
     pub fn damage(&mut self, amount: f32) {
         self.current = (self.current - amount).max(0.0);
     }
 
+    /// Patch up to the maximum, never past it
+    pub fn heal(&mut self, amount: f32) {
+        self.current = (self.current + amount).min(self.max);
+    }
+
     pub fn is_dead(&self) -> bool {
         self.current <= 0.0
     }
 }
 
-#[derive(Component)]
+/// The broad kinds of harm, what Resistances are measured against
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DamageKind {
+    Impact,
+    Explosion,
+    EnemyFire,
+    Hazard,
+    SelfInflicted,
+}
+
+/// Where some damage came from and how much it carries
+///
+/// Rust Concept: Enums carrying different data per variant
+/// Most sources are a plain amount, already scaled for how hard the hit was. An
+/// explosion carries its centre and reach instead, so every target works out
+/// its own share from how far away it was.
+#[derive(Component, Debug, Clone, Copy, PartialEq)]
 pub enum DamageSource {
-    AsteroidEntity(AsteroidSize),
+    Impact(f32),  // ramming or being rammed by a rock
+    Contact(f32), // staying pressed against a rock, already scaled for the tick
+    Explosion {
+        center: Vec3,
+        radius: f32,
+        damage: f32, // at the centre, fading to nothing at the edge
+    },
+    // nothing in the game deals these yet
+    #[allow(dead_code)]
+    EnemyFire(f32),
+    #[allow(dead_code)]
+    Hazard(f32),
+    #[allow(dead_code)]
+    SelfInflicted(f32),
 }
 
 impl DamageSource {
-    pub fn damage(&self) -> f32 {
+    pub fn kind(&self) -> DamageKind {
+        match self {
+            DamageSource::Impact(_) | DamageSource::Contact(_) => DamageKind::Impact,
+            DamageSource::Explosion { .. } => DamageKind::Explosion,
+            DamageSource::EnemyFire(_) => DamageKind::EnemyFire,
+            DamageSource::Hazard(_) => DamageKind::Hazard,
+            DamageSource::SelfInflicted(_) => DamageKind::SelfInflicted,
+        }
+    }
+
+    /// Damage to something at `position`, before its resistances
+    /// For explosions the distance is measured to the target's edge, so big rocks
+    /// get caught sooner
+    pub fn damage_at(&self, position: Vec3, target_radius: f32) -> f32 {
         match *self {
-            DamageSource::AsteroidEntity(size) => size.damage(),
+            DamageSource::Explosion {
+                center,
+                radius,
+                damage,
+            } => {
+                let distance = (center.distance(position) - target_radius).max(0.0);
+                damage * (1.0 - distance / radius).max(0.0)
+            }
+            DamageSource::Impact(damage)
+            | DamageSource::Contact(damage)
+            | DamageSource::EnemyFire(damage)
+            | DamageSource::Hazard(damage)
+            | DamageSource::SelfInflicted(damage) => damage,
         }
     }
+
+    /// A sudden hit rather than a steady grind, these make a noise and start invulnerability
+    pub fn is_hit(&self) -> bool {
+        !matches!(self, DamageSource::Contact(_))
+    }
+}
+
+/// How much of each kind of damage something shrugs off
+/// 0 takes all of it, 1 is immune, and below 0 is a weakness that takes extra
+#[derive(Component, Debug, Clone, Copy, Default, PartialEq)]
+pub struct Resistances {
+    pub impact: f32,
+    pub explosion: f32,
+    pub enemy_fire: f32,
+    pub hazard: f32,
+    pub self_inflicted: f32,
 }
 
-// Asteroid size categories
+impl Resistances {
+    pub fn against(&self, kind: DamageKind) -> f32 {
+        match kind {
+            DamageKind::Impact => self.impact,
+            DamageKind::Explosion => self.explosion,
+            DamageKind::EnemyFire => self.enemy_fire,
+            DamageKind::Hazard => self.hazard,
+            DamageKind::SelfInflicted => self.self_inflicted,
+        }
+    }
+
+    /// What's left of `damage` of this kind once it gets through
+    pub fn reduce(&self, kind: DamageKind, damage: f32) -> f32 {
+        damage * (1.0 - self.against(kind).min(1.0))
+    }
+}
+
+/// How big an asteroid is, measured from its actual outline
+///
+/// Mass, damage, toughness and score all scale smoothly with the rock's real
+/// area, so no two rocks need to be quite the same. How steeply is up to the
+/// AsteroidTuning loaded from the game config.
 #[derive(Component, Debug, Clone, Copy, PartialEq)]
-pub enum AsteroidSize {
-    Small,
-    Medium,
-    Large,
+pub struct AsteroidSize {
+    pub area: f32,
 }
 
 impl AsteroidSize {
-    // get the radius
+    pub fn from_outline(vertices: &[Vec2]) -> Self {
+        Self {
+            area: polygon_area(vertices),
+        }
+    }
+
+    /// Radius of a circle with the same area
     pub fn radius(&self) -> f32 {
+        (self.area / std::f32::consts::PI).sqrt()
+    }
+
+    // get the mass for physics
+    pub fn mass(&self, tuning: &AsteroidTuning) -> f32 {
+        self.area * tuning.density
+    }
+
+    // how much projectile damage it takes to break, heavier rocks take more
+    pub fn hit_points(&self, tuning: &AsteroidTuning) -> f32 {
+        self.mass(tuning) * tuning.hit_points_per_mass
+    }
+
+    // small rocks are harder to hit, so they're worth more
+    pub fn score(&self, tuning: &AsteroidTuning) -> u32 {
+        (tuning.score_scale / self.radius().max(1.0)).round() as u32
+    }
+
+    pub fn can_split(&self, tuning: &AsteroidTuning) -> bool {
+        self.radius() >= tuning.min_split_radius
+    }
+}
+
+/// What an asteroid is made of
+///
+/// Changes how it looks, how heavy and tough it is, how it bounces, and what
+/// happens when it breaks: ice shatters, explosives go off, crystals leave a gem
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+pub enum AsteroidMaterial {
+    #[default]
+    Rock,
+    Ice,
+    Iron,
+    Explosive,
+    Crystal,
+}
+
+impl AsteroidMaterial {
+    pub fn color(&self) -> Color {
         match self {
-            Self::Small => 15.0,
-            Self::Medium => 30.0,
-            Self::Large => 45.0,
+            Self::Rock => Color::srgb(0.5, 0.5, 0.7),
+            Self::Ice => Color::srgb(0.7, 0.9, 1.0),
+            Self::Iron => Color::srgb(0.55, 0.35, 0.3),
+            Self::Explosive => Color::srgb(0.9, 0.35, 0.15),
+            Self::Crystal => Color::srgb(0.5, 1.0, 0.7),
         }
     }
 
-    // get the mass for physics
-    pub fn mass(&self) -> f32 {
+    /// Mass for the same area, compared to plain rock
+    pub fn density(&self) -> f32 {
+        match self {
+            Self::Rock => 1.0,
+            Self::Ice => 0.6,
+            Self::Iron => 2.5,
+            Self::Explosive => 0.8,
+            Self::Crystal => 1.2,
+        }
+    }
+
+    /// Hit points for the same mass, compared to plain rock
+    pub fn toughness(&self) -> f32 {
         match self {
-            Self::Small => 7.0,
-            Self::Medium => 15.0,
-            Self::Large => 35.0,
+            Self::Rock | Self::Crystal => 1.0,
+            Self::Ice => 0.5,
+            Self::Iron => 1.2,
+            Self::Explosive => 0.6,
         }
     }
 
-    // number of vertices for the polygon
-    pub fn vertex_count(&self) -> usize {
+    // bounciness when rocks knock into each other
+    pub fn restitution(&self) -> f32 {
         match self {
-            Self::Small => 10,
-            Self::Medium => 17,
-            Self::Large => 27,
+            Self::Rock => 0.8,
+            Self::Ice => 0.4,
+            Self::Iron => 0.3,
+            Self::Explosive => 0.6,
+            Self::Crystal => 0.9,
         }
     }
 
-    pub fn damage(&self) -> f32 {
+    /// How many times a breaking rock is cut, each cut splits every piece again
+    pub fn fracture_cuts(&self) -> usize {
         match self {
-            Self::Small => 10.0,
-            Self::Medium => 20.0,
-            Self::Large => 30.0,
+            Self::Ice => 3, // up to 8 shards
+            _ => 1,
+        }
+    }
+}
+
+/// Briefly lights up an asteroid that just took a hit
+#[derive(Component)]
+pub struct HitFlash {
+    pub timer: Timer,
+}
+
+impl Default for HitFlash {
+    fn default() -> Self {
+        Self {
+            timer: Timer::from_seconds(0.12, TimerMode::Once),
         }
     }
 }
@@ -96,8 +282,25 @@ impl AsteroidSize {
 #[derive(Component)]
 pub struct Cleanup;
 
+/// A shot in flight, moved by its LinearVelocity like any other body
 #[derive(Component)]
-pub struct Projectile;
+pub struct Projectile {
+    pub damage: f32,
+    pub piercing: bool,           // keeps going through everything it hits
+    pub already_hit: Vec<Entity>, // rocks a piercing shot has been through, each is only hit once
+    pub share: f32, // how much of its trigger pull this shot is, for the director's accuracy
+}
+
+impl Projectile {
+    pub fn new(damage: f32, piercing: bool) -> Self {
+        Self {
+            damage,
+            piercing,
+            already_hit: Vec::new(),
+            share: 1.0,
+        }
+    }
+}
 
 #[derive(Component)]
 pub struct Lifetime {
@@ -126,3 +329,343 @@ pub enum ThrusterType {
 
 #[derive(Component)]
 pub struct ThrusterAudio;
+
+/// Something the player can fly into and collect
+#[derive(Component, Debug, Clone, Copy, PartialEq)]
+pub enum PowerUp {
+    Shield,
+    ScoreBonus(u32), // a gem left behind by a crystal asteroid
+}
+
+/// A force field around the ship, spawned as a child of the player
+/// Soaks up damage until its hit points or its time run out
+#[derive(Component)]
+pub struct Shield {
+    hit_points: f32,
+    max_hit_points: f32,
+    pub remaining: Timer,
+    pub flash: Timer, // runs after every hit, tints the field
+}
+
+impl Shield {
+    pub fn new(hit_points: f32, seconds: f32) -> Self {
+        let mut flash = Timer::from_seconds(0.2, TimerMode::Once);
+        flash.tick(flash.duration()); // start without a flash
+        Self {
+            hit_points,
+            max_hit_points: hit_points,
+            remaining: Timer::from_seconds(seconds, TimerMode::Once),
+            flash,
+        }
+    }
+
+    /// Take a hit, returns the damage the shield couldn't hold
+    pub fn absorb(&mut self, amount: f32) -> f32 {
+        let absorbed = amount.min(self.hit_points);
+        self.hit_points -= absorbed;
+        self.flash.reset();
+        amount - absorbed
+    }
+
+    /// Fraction of hit points left, 0.0 to 1.0
+    pub fn strength(&self) -> f32 {
+        self.hit_points / self.max_hit_points
+    }
+
+    pub fn is_down(&self) -> bool {
+        self.hit_points <= 0.0 || self.remaining.is_finished()
+    }
+}
+
+/// A moment of grace after the ship takes a hit: it ignores all damage and blinks
+/// Always on the player, idle between hits, so two hits in one tick can't both land
+#[derive(Component)]
+pub struct Invulnerability {
+    pub timer: Timer,
+}
+
+impl Default for Invulnerability {
+    fn default() -> Self {
+        let mut timer = Timer::from_seconds(0.0, TimerMode::Once);
+        timer.tick(timer.duration()); // start vulnerable
+        Self { timer }
+    }
+}
+
+impl Invulnerability {
+    pub fn start(&mut self, seconds: f32) {
+        self.timer = Timer::from_seconds(seconds, TimerMode::Once);
+    }
+
+    pub fn is_active(&self) -> bool {
+        !self.timer.is_finished()
+    }
+}
+
+/// Handling and weapon numbers for the player's ship
+/// Built from the selected ShipType when the player spawns
+#[derive(Component, Debug, Clone)]
+pub struct ShipStats {
+    pub thruster_force: f32,
+    pub rotation_torque: f32,
+    pub reverse_thrust_force: f32,
+    pub projectile_speed: f32,
+    pub projectile_lifetime: f32,
+    pub projectile_size: Vec2,
+    pub muzzle_offset: f32, // distance from ship center to where shots spawn
+}
+
+/// The kinds of gun a ship can switch between
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WeaponType {
+    Blaster,
+    SpreadShot,
+    RapidFire,
+    Beam,
+}
+
+impl WeaponType {
+    pub const ALL: [WeaponType; 4] = [
+        WeaponType::Blaster,
+        WeaponType::SpreadShot,
+        WeaponType::RapidFire,
+        WeaponType::Beam,
+    ];
+
+    pub fn next(&self) -> Self {
+        let index = Self::ALL.iter().position(|w| w == self).unwrap_or(0);
+        Self::ALL[(index + 1) % Self::ALL.len()]
+    }
+
+    pub fn display_name(&self) -> &'static str {
+        match self {
+            Self::Blaster => "Blaster",
+            Self::SpreadShot => "Spread Shot",
+            Self::RapidFire => "Rapid Fire",
+            Self::Beam => "Charge Beam",
+        }
+    }
+}
+
+/// The ship's gun: what it shoots and how often
+///
+/// Built from the ship's ShipStats, so a Rocket's spread shot still flies like a Rocket's
+#[derive(Component, Debug, Clone)]
+pub struct Weapon {
+    pub weapon_type: WeaponType,
+    pub fire_interval: f32, // seconds between shots
+    pub projectile_speed: f32,
+    pub projectile_lifetime: f32,
+    pub projectile_size: Vec2,
+    pub projectile_count: u32,
+    pub spread: f32, // radians between the outermost shots
+    pub damage: f32,
+    pub piercing: bool,
+    pub charge_time: f32, // seconds to a full charge, 0.0 for guns that fire on press
+    cooldown: f32,
+    charge: f32,
+}
+
+impl Weapon {
+    /// A charged weapon needs at least this much charge to fire on release
+    pub const MIN_CHARGE: f32 = 0.25;
+
+    pub fn new(weapon_type: WeaponType, stats: &ShipStats) -> Self {
+        let base = Self {
+            weapon_type,
+            fire_interval: 0.25,
+            projectile_speed: stats.projectile_speed,
+            projectile_lifetime: stats.projectile_lifetime,
+            projectile_size: stats.projectile_size,
+            projectile_count: 1,
+            spread: 0.0,
+            damage: 10.0,
+            piercing: false,
+            charge_time: 0.0,
+            cooldown: 0.0,
+            charge: 0.0,
+        };
+
+        match weapon_type {
+            WeaponType::Blaster => base,
+            // a fan of weak, short-lived shots
+            WeaponType::SpreadShot => Self {
+                fire_interval: 0.5,
+                projectile_speed: base.projectile_speed * 0.9,
+                projectile_lifetime: base.projectile_lifetime * 0.6,
+                projectile_size: base.projectile_size * 0.8,
+                projectile_count: 5,
+                spread: 0.6,
+                damage: 5.0,
+                ..base
+            },
+            // a stream of small fast shots
+            WeaponType::RapidFire => Self {
+                fire_interval: 0.08,
+                projectile_speed: base.projectile_speed * 1.2,
+                projectile_size: base.projectile_size * 0.6,
+                damage: 4.0,
+                ..base
+            },
+            // hold to charge, let go to fire a bolt that goes through everything
+            WeaponType::Beam => Self {
+                fire_interval: 0.5,
+                projectile_speed: base.projectile_speed * 2.0,
+                projectile_lifetime: base.projectile_lifetime * 0.5,
+                projectile_size: Vec2::new(base.projectile_size.x * 0.6, 80.0),
+                damage: 40.0,
+                piercing: true,
+                charge_time: 1.0,
+                ..base
+            },
+        }
+    }
+
+    /// Advance the gun by one tick and decide whether it goes off
+    ///
+    /// `held` is the fire button right now, `pressed` catches taps shorter than a tick.
+    /// Returns how strong the shot is (1.0 for normal guns, the charge for the beam).
+    pub fn trigger(&mut self, held: bool, pressed: bool, delta: f32) -> Option<f32> {
+        self.cooldown = (self.cooldown - delta).max(0.0);
+
+        if self.charge_time <= 0.0 {
+            // auto-fire while held, as fast as the cooldown allows
+            if (held || pressed) && self.cooldown <= 0.0 {
+                self.cooldown = self.fire_interval;
+                return Some(1.0);
+            }
+            return None;
+        }
+
+        if held || pressed {
+            self.charge = (self.charge + delta).min(self.charge_time);
+            return None;
+        }
+
+        // released: fire whatever charge built up
+        let power = self.charge_fraction();
+        self.charge = 0.0;
+        if power >= Self::MIN_CHARGE && self.cooldown <= 0.0 {
+            self.cooldown = self.fire_interval;
+            return Some(power);
+        }
+        None
+    }
+
+    /// How charged a charging weapon is, 0.0 to 1.0
+    pub fn charge_fraction(&self) -> f32 {
+        if self.charge_time <= 0.0 {
+            0.0
+        } else {
+            self.charge / self.charge_time
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn stats() -> ShipStats {
+        ShipStats {
+            thruster_force: 1.0,
+            rotation_torque: 1.0,
+            reverse_thrust_force: 1.0,
+            projectile_speed: 500.0,
+            projectile_lifetime: 2.0,
+            projectile_size: Vec2::new(10.0, 20.0),
+            muzzle_offset: 30.0,
+        }
+    }
+
+    #[test]
+    fn test_asteroid_size_scales_with_area() {
+        let circle_of = |radius: f32| AsteroidSize {
+            area: std::f32::consts::PI * radius * radius,
+        };
+        let (small, large) = (circle_of(15.0), circle_of(45.0));
+        let tuning = AsteroidTuning::default();
+
+        assert!((small.radius() - 15.0).abs() < 1e-3);
+        assert!(large.mass(&tuning) > small.mass(&tuning));
+        assert!(small.score(&tuning) > large.score(&tuning));
+        // one blaster shot is enough for a small rock
+        let blaster = Weapon::new(WeaponType::Blaster, &stats());
+        assert!(small.hit_points(&tuning) <= blaster.damage);
+        assert!(!small.can_split(&tuning) && large.can_split(&tuning));
+    }
+
+    #[test]
+    fn test_damage_model() {
+        let blast = DamageSource::Explosion {
+            center: Vec3::ZERO,
+            radius: 100.0,
+            damage: 40.0,
+        };
+        assert_eq!(blast.damage_at(Vec3::ZERO, 0.0), 40.0);
+        assert_eq!(blast.damage_at(Vec3::new(50.0, 0.0, 0.0), 0.0), 20.0);
+        assert_eq!(blast.damage_at(Vec3::new(150.0, 0.0, 0.0), 0.0), 0.0);
+        // a big target gets caught by its edge
+        assert_eq!(blast.damage_at(Vec3::new(150.0, 0.0, 0.0), 100.0), 40.0);
+
+        let armour = Resistances {
+            impact: 0.25,
+            explosion: -0.5,
+            hazard: 2.0,
+            ..default()
+        };
+        assert_eq!(armour.reduce(DamageKind::Impact, 20.0), 15.0);
+        assert_eq!(armour.reduce(DamageKind::Explosion, 20.0), 30.0);
+        assert_eq!(armour.reduce(DamageKind::Hazard, 20.0), 0.0);
+        assert_eq!(armour.reduce(DamageKind::EnemyFire, 20.0), 20.0);
+
+        let mut health = Health::new(100.0);
+        health.damage(30.0);
+        health.heal(50.0);
+        assert_eq!(health.current(), health.max());
+    }
+
+    #[test]
+    fn test_invulnerability_wears_off() {
+        let mut invulnerability = Invulnerability::default();
+        assert!(!invulnerability.is_active());
+
+        invulnerability.start(1.0);
+        let step = std::time::Duration::from_secs_f32(0.6);
+        invulnerability.timer.tick(step);
+        assert!(invulnerability.is_active());
+        invulnerability.timer.tick(step);
+        assert!(!invulnerability.is_active());
+    }
+
+    #[test]
+    fn test_auto_fire_respects_cooldown() {
+        let mut weapon = Weapon::new(WeaponType::Blaster, &stats());
+        let tick = 1.0 / 64.0;
+
+        // holding the trigger for a second fires once per fire_interval
+        let shots = (0..64)
+            .filter(|_| weapon.trigger(true, false, tick).is_some())
+            .count();
+        assert_eq!(shots, 4);
+        assert_eq!(weapon.trigger(false, false, tick), None);
+    }
+
+    #[test]
+    fn test_beam_fires_on_release() {
+        let mut weapon = Weapon::new(WeaponType::Beam, &stats());
+        let tick = 0.1;
+
+        // a tap isn't enough charge
+        assert_eq!(weapon.trigger(true, true, tick), None);
+        assert_eq!(weapon.trigger(false, false, tick), None);
+
+        for _ in 0..5 {
+            assert_eq!(weapon.trigger(true, false, tick), None);
+        }
+        let power = weapon
+            .trigger(false, false, tick)
+            .expect("half a charge fires");
+        assert!((power - 0.5).abs() < 1e-5);
+    }
+}
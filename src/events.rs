@@ -10,6 +10,10 @@ pub enum PlaySoundEvent {
     GameStart,
     GameOver,
     MenuBoop,
+    ShieldUp,
+    ShieldHit,
+    ShieldDown,
+    LowHealth,
 }
 
 pub fn handle_audio_events(
@@ -43,23 +47,121 @@ pub fn handle_audio_events(
                 AudioPlayer(audio_assets.menu_boop.clone()),
                 PlaybackSettings::DESPAWN,
             )),
+            PlaySoundEvent::ShieldUp => commands.spawn((
+                AudioPlayer(audio_assets.shield_up.clone()),
+                PlaybackSettings::DESPAWN,
+            )),
+            PlaySoundEvent::ShieldHit => commands.spawn((
+                AudioPlayer(audio_assets.shield_hit.clone()),
+                PlaybackSettings::DESPAWN,
+            )),
+            PlaySoundEvent::ShieldDown => commands.spawn((
+                AudioPlayer(audio_assets.shield_down.clone()),
+                PlaybackSettings::DESPAWN,
+            )),
+            // the menu's glitch, slowed down into a warning
+            PlaySoundEvent::LowHealth => commands.spawn((
+                AudioPlayer(audio_assets.menu_boop.clone()),
+                PlaybackSettings::DESPAWN.with_speed(0.6),
+            )),
         };
     }
 }
 
+/// An explosive asteroid going off
+/// Everything in range takes damage, less the further it is from the middle
+#[derive(Event, Message, Clone, Copy)]
+pub struct BlastEvent {
+    pub position: Vec3,
+    pub radius: f32,
+    pub damage: f32,
+}
+
+impl BlastEvent {
+    /// Bigger explosive rocks reach further and hit harder
+    pub fn from_asteroid(position: Vec3, size: AsteroidSize) -> Self {
+        Self {
+            position,
+            radius: 60.0 + size.radius() * 2.0,
+            damage: size.radius(),
+        }
+    }
+
+    /// The blast as damage, fading out towards its edge
+    pub fn source(&self) -> DamageSource {
+        DamageSource::Explosion {
+            center: self.position,
+            radius: self.radius,
+            damage: self.damage,
+        }
+    }
+
+    /// Damage to something at `position`, None if it's out of range
+    pub fn damage_at(&self, position: Vec3, target_radius: f32) -> Option<f32> {
+        let damage = self.source().damage_at(position, target_radius);
+        (damage > 0.0).then_some(damage)
+    }
+}
+
+/// A shot reaching an asteroid, found by sweeping the shot along its path
+#[derive(Event, Message, Clone, Copy)]
+pub struct ProjectileHit {
+    pub projectile: Entity,
+    pub asteroid: Entity,
+    pub point: Vec2,    // where on the rock it struck
+    pub velocity: Vec2, // the shot's velocity
+    pub share: f32,     // its part of a trigger pull, 0 past the shot's first rock
+}
+
+/// An asteroid broken for good, by a shot or a blast
+/// Written once per rock, whether or not it splits into pieces
+#[derive(Event, Message, Clone, Copy)]
+pub struct AsteroidDestroyed {
+    pub position: Vec3,
+    pub size: AsteroidSize,
+}
+
 #[derive(Event, Message)]
 pub struct DamageEvent {
     pub player: Entity,
     pub position: Vec3,
+    pub from: Vec3, // where the damage came from, for the direction indicator
     pub source_type: DamageSource,
 }
 
-// #[derive(Event, Message)]
-// pub struct HealthChanged {
-//     pub player: Entity,
-//     pub new_health: f32,
-//     pub position: Vec3,
-// }
+impl DamageEvent {
+    /// Damage where the player was hit, before resistances and shields
+    pub fn damage(&self) -> f32 {
+        self.source_type.damage_at(self.position, 0.0)
+    }
+}
+
+/// Hull patched up, like between levels
+#[derive(Event, Message)]
+pub struct HealEvent {
+    pub player: Entity,
+    pub amount: f32,
+}
+
+/// Something's health moved, the ship's or a rock's, written for every change
+/// so the UI, audio and stats never have to poll for it
+#[derive(Event, Message, Clone, Copy)]
+pub struct HealthChanged {
+    pub entity: Entity,
+    pub previous: f32,
+    pub new_health: f32,
+    pub max_health: f32,
+    pub position: Vec3,
+    pub from: Vec3, // where the damage came from, position for repairs
+    pub cause: Option<DamageSource>, // None for healing
+}
+
+impl HealthChanged {
+    /// Negative for damage
+    pub fn change(&self) -> f32 {
+        self.new_health - self.previous
+    }
+}
 
 #[derive(Event, Message)]
 pub struct DeathEvent {
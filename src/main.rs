@@ -8,6 +8,7 @@
 //! - Idiomatic Rust patterns
 
 use avian2d::prelude::*;
+use bevy::input::InputSystems;
 use bevy::prelude::*;
 use bevy_hanabi::prelude::*;
 
@@ -15,6 +16,7 @@ mod components;
 mod events;
 mod resources;
 mod shapes;
+mod storage;
 mod systems;
 
 use events::*;
@@ -55,81 +57,191 @@ fn main() {
         .insert_resource(Gravity(Vec2::ZERO)) // No gravity in space!
         // Initialize our game resources
         .init_state::<AppState>()
+        .add_sub_state::<PlayState>()
         .init_resource::<GameData>()
         .init_resource::<SpawnTimer>()
+        .init_resource::<LevelProgress>()
         .init_resource::<AsteroidSpawnConfig>()
+        .init_resource::<ShapeRegistry>()
+        .init_resource::<MaterialRegistry>()
+        .init_resource::<AsteroidTuning>()
+        .init_resource::<PowerUpConfig>()
+        .init_resource::<PowerUpTimer>()
         .init_resource::<PhysicsConfig>()
         .init_resource::<DifficultyConfig>()
+        .init_resource::<DirectorConfig>()
+        .init_resource::<Director>()
+        .init_resource::<DamageConfig>()
         .init_resource::<ScreenShake>()
         .init_resource::<CameraTarget>()
+        .init_resource::<HighScores>()
+        .init_resource::<NameEntry>()
+        .init_resource::<PlayerSettings>()
+        .insert_resource(SeedSettings::from_args(
+            &std::env::args().collect::<Vec<_>>(),
+        ))
+        .init_resource::<GameRng>()
+        .init_resource::<ShipControls>()
+        .init_resource::<InputBindings>()
+        .init_resource::<RunMode>()
+        .init_resource::<LoadedReplay>()
+        .init_resource::<TouchControlsEnabled>()
         // Startup systems (run once at launch)
         // Rust Concept: System scheduling with tuples
+        .init_asset::<GameConfig>()
+        .init_asset_loader::<RonLoader<GameConfig>>()
+        .init_asset::<Campaign>()
+        .init_asset_loader::<RonLoader<Campaign>>()
         .add_systems(OnEnter(AppState::Loading), setup_loading)
         .add_systems(Update, (check_loading).run_if(in_state(AppState::Loading)))
         .add_message::<PlaySoundEvent>()
         .add_message::<DamageEvent>()
-        // .add_message::<HealthChanged>()
+        .add_message::<BlastEvent>()
+        .add_message::<ProjectileHit>()
+        .add_message::<AsteroidDestroyed>()
+        .add_message::<HealEvent>()
+        .add_message::<HealthChanged>()
         .add_message::<DeathEvent>()
-        .add_systems(Update, (handle_audio_events,))
+        .add_systems(
+            Update,
+            (handle_audio_events, detect_touch, apply_game_config),
+        )
         .add_systems(OnEnter(AppState::Menu), (setup_menu,))
         .add_systems(
             Update,
-            (handle_menu_input,).run_if(in_state(AppState::Menu)),
+            (handle_seed_entry, handle_menu_input).run_if(in_state(AppState::Menu)),
+        )
+        .add_systems(OnEnter(AppState::ShipSelect), setup_ship_select)
+        .add_systems(
+            Update,
+            (handle_ship_select_input, update_ship_cards)
+                .chain()
+                .run_if(in_state(AppState::ShipSelect)),
+        )
+        .add_systems(
+            OnEnter(AppState::Playing),
+            (reset_run, spawn_player, spawn_touch_controls),
+        )
+        .add_systems(OnEnter(AppState::Restarting), restart_run)
+        // Pausing
+        .add_systems(
+            Update,
+            (toggle_pause, pause_on_focus_lost, touch_pause).run_if(in_state(AppState::Playing)),
+        )
+        .add_systems(OnEnter(PlayState::Paused), (enter_pause, setup_pause_menu))
+        .add_systems(OnExit(PlayState::Paused), exit_pause)
+        .add_systems(
+            Update,
+            handle_pause_menu_input.run_if(in_state(PlayState::Paused)),
         )
-        .add_systems(OnEnter(AppState::Playing), (spawn_player,))
         // .add_systems(OnEnter(AppState::GameOver))
         .add_systems(
             Startup,
             (
                 setup_camera,
                 spawn_background,
-                setup_health_display,
+                setup_health_bar,
+                setup_damage_vignette,
+                setup_weapon_display,
                 setup_score_display,
+                setup_level_display,
+                setup_director_display,
+                load_high_scores,
+                load_replay,
+                load_input_bindings,
                 // WASM-specific: Add browser logging setup
                 #[cfg(target_arch = "wasm32")]
                 setup_browser_logging,
             ),
         )
-        // Update systems (run every frame)
-        // Rust Concept: System sets for organization
+        // Input is sampled once per frame, right after Bevy reads the devices
         .add_systems(
-            Update,
+            PreUpdate,
+            (read_ship_controls, read_touch_controls)
+                .chain()
+                .after(InputSystems)
+                .run_if(in_state(PlayState::Running)),
+        )
+        // Simulation systems (run every fixed tick)
+        // Rust Concept: Deterministic ordering with chain()
+        // A fixed timestep and a fixed system order means the same seed and the
+        // same inputs always play out the same way, which is what replays rely on
+        .add_systems(
+            FixedUpdate,
             (
                 // Input handling
+                feed_replay_input,
+                record_input,
                 player_movement,
+                constrain_player_position,
                 player_fire,
                 // Spawning and cleanup
-                spawn_asteroids,
+                run_level,
+                spawn_asteroids.run_if(endless_mode),
+                spawn_power_ups,
                 cleanup_offscreen,
-                trigger_screen_shake,
-                update_screen_shake,
-                apply_camera_position,
-                move_projectiles,
-                cleanup_projectiles,
+                sweep_projectiles,
                 // Physics and collision (handled by Avian automatically)
                 handle_collisions_simple,
+                handle_sustained_contact,
                 handle_projectile_collisions,
+                handle_blasts,
+                collect_power_ups,
                 //event handlers
                 handle_health_message,
+                update_director,
                 handle_death_message,
+                update_shields,
+                update_invulnerability,
+                clear_latched_input,
+            )
+                .chain()
+                .run_if(in_state(PlayState::Running)),
+        )
+        // Lifetimes keep ticking behind the GameOver screen so the death explosion burns out
+        .add_systems(
+            FixedUpdate,
+            cleanup_projectiles
+                .after(clear_latched_input)
+                .run_if(in_state(PlayState::Running).or(in_state(AppState::GameOver))),
+        )
+        // Update systems (run every frame)
+        .add_systems(
+            Update,
+            (
+                trigger_screen_shake,
+                update_screen_shake,
+                apply_camera_position,
                 handle_audio_events,
                 // UI updates
-                update_health_display,
+                spawn_damage_indicators,
+                update_damage_indicators,
                 update_score_display,
+                update_weapon_display,
+                update_level_display,
+                update_intermission_overlay,
+                update_director_display,
+                update_asteroid_damage,
                 update_thruster_visuals,
                 update_thruster_audio,
+                show_touch_controls,
                 // Rendering (debug visualization)
                 //draw_asteroid_shapes,
             )
-                .run_if(in_state(AppState::Playing)),
+                .run_if(in_state(PlayState::Running)),
+        )
+        .add_systems(OnEnter(AppState::GameOver), (setup_game_over, save_replay))
+        // The hull bar drains to empty and the last flash fades behind the GameOver screen
+        .add_systems(
+            Update,
+            (update_health_bar, warn_low_health, update_damage_vignette)
+                .run_if(in_state(PlayState::Running).or(in_state(AppState::GameOver))),
         )
-        // Rust Concept: System ordering
-        // We can specify that certain systems run before others
-        .add_systems(Update, constrain_player_position.after(player_movement))
-        .add_systems(OnEnter(AppState::GameOver), setup_game_over)
         .add_systems(
             Update,
-            (handle_game_over_input).run_if(in_state(AppState::GameOver)),
+            (handle_name_entry, handle_game_over_input)
+                .chain()
+                .run_if(in_state(AppState::GameOver)),
         )
         .run();
 }
@@ -173,4 +285,118 @@ mod tests {
         // If this doesn't panic, our setup is valid
         app.update();
     }
+
+    fn go_to(app: &mut App, state: AppState) {
+        app.world_mut()
+            .resource_mut::<NextState<AppState>>()
+            .set(state);
+        app.update();
+    }
+
+    fn count<F: bevy::ecs::query::QueryFilter>(app: &mut App) -> usize {
+        app.world_mut()
+            .query_filtered::<(), F>()
+            .iter(app.world())
+            .count()
+    }
+
+    #[test]
+    fn test_game_over_cycle_starts_clean() {
+        use crate::components::{Asteroid, AsteroidMaterial, Health, Lifetime, Player, Projectile};
+        use bevy::ecs::system::RunSystemOnce;
+        use bevy::state::app::StatesPlugin;
+
+        // Rust Concept: Running real systems headlessly
+        // No renderer or audio here, so the asset stores are registered by hand
+        let mut app = App::new();
+        app.add_plugins((MinimalPlugins, AssetPlugin::default(), StatesPlugin))
+            .init_asset::<Mesh>()
+            .init_asset::<ColorMaterial>()
+            .init_asset::<EffectAsset>()
+            .init_asset::<Image>()
+            .init_asset::<AudioSource>()
+            .init_state::<AppState>()
+            .add_sub_state::<PlayState>()
+            .init_resource::<ShipControls>()
+            .init_resource::<RunMode>()
+            .init_resource::<GameData>()
+            .init_resource::<SpawnTimer>()
+            .init_resource::<ScreenShake>()
+            .init_resource::<CameraTarget>()
+            .init_resource::<PhysicsConfig>()
+            .init_resource::<DirectorConfig>()
+            .init_resource::<PlayerSettings>()
+            .init_resource::<SeedSettings>()
+            .init_resource::<GameRng>()
+            .init_asset::<GameConfig>()
+            .init_asset::<Campaign>()
+            .insert_resource(GameConfigHandle(Handle::default()))
+            .insert_resource(CampaignHandle(Handle::default()))
+            .add_message::<PlaySoundEvent>()
+            .add_systems(OnEnter(AppState::Playing), (reset_run, spawn_player));
+
+        go_to(&mut app, AppState::Playing);
+        assert_eq!(count::<With<Player>>(&mut app), 1);
+
+        // Make a mess: rocks, a shot, an explosion, and a run's worth of progress
+        app.world_mut()
+            .run_system_once(
+                |mut commands: Commands,
+                 mut meshes: ResMut<Assets<Mesh>>,
+                 mut materials: ResMut<Assets<ColorMaterial>>,
+                 mut effects: ResMut<Assets<EffectAsset>>,
+                 mut game_rng: ResMut<GameRng>| {
+                    spawn_asteroid_entity(
+                        &mut commands,
+                        &mut meshes,
+                        &mut materials,
+                        &mut game_rng.shapes,
+                        &ShapeRegistry::default(),
+                        &AsteroidTuning::default(),
+                        Vec3::new(0.0, 200.0, 0.0),
+                        Vec2::new(0.0, -100.0),
+                        45.0,
+                        AsteroidMaterial::Rock,
+                    );
+                    spawn_explosion(&mut commands, &mut effects, Vec3::ZERO);
+                },
+            )
+            .expect("spawning test entities failed");
+        app.world_mut().resource_mut::<ShipControls>().fire_pressed = true;
+        app.world_mut()
+            .run_system_once(player_fire)
+            .expect("player_fire failed");
+
+        app.world_mut().resource_mut::<GameData>().score = 500;
+        app.world_mut().resource_mut::<SpawnTimer>().elapsed_time = 120.0;
+        app.world_mut().resource_mut::<ScreenShake>().trauma = 1.0;
+        app.world_mut().resource_mut::<CameraTarget>().position = Vec3::new(10.0, 5.0, 0.0);
+        app.update();
+
+        assert_eq!(count::<With<Asteroid>>(&mut app), 1);
+        assert_eq!(count::<With<Projectile>>(&mut app), 1);
+        assert_eq!(count::<With<Lifetime>>(&mut app), 2);
+
+        go_to(&mut app, AppState::GameOver);
+        go_to(&mut app, AppState::Menu);
+        go_to(&mut app, AppState::Playing);
+
+        // Only the fresh player is left
+        assert_eq!(count::<With<Asteroid>>(&mut app), 0);
+        assert_eq!(count::<With<Projectile>>(&mut app), 0);
+        assert_eq!(count::<With<Lifetime>>(&mut app), 0);
+        assert_eq!(count::<With<Player>>(&mut app), 1);
+
+        let world = app.world_mut();
+        let health = world
+            .query_filtered::<&Health, With<Player>>()
+            .single(world)
+            .expect("exactly one player");
+        assert_eq!(health.current(), 100.0);
+
+        assert_eq!(world.resource::<GameData>().score, 0);
+        assert_eq!(world.resource::<SpawnTimer>().elapsed_time, 0.0);
+        assert_eq!(world.resource::<ScreenShake>().trauma, 0.0);
+        assert_eq!(world.resource::<CameraTarget>().position, Vec3::ZERO);
+    }
 }
@@ -1,31 +1,527 @@
 // Resources are singletons, one instance per app
+use crate::components::{AsteroidMaterial, Resistances, ShipStats};
+use crate::shapes::ShapeKind;
+use avian2d::prelude::*;
 use bevy::prelude::*;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use serde::Deserialize;
+use std::collections::HashMap;
 
 #[derive(Debug, States, Clone, PartialEq, Eq, Hash, Default)]
 pub enum AppState {
     #[default]
     Loading,
-    Menu,     // Start Screen
-    Playing,  // Active Gameplay - will have levels and such
-    GameOver, // Game Over Screen + High Scores }
+    Menu,       // Start Screen
+    ShipSelect, // Pick a ship before the run
+    Playing,    // Active Gameplay, the campaign's levels then endless mode
+    Restarting, // Passes straight back to Playing so the run lifecycle runs again
+    GameOver,   // Game Over Screen + High Scores }
 }
 
-// #[derive(Resource)]
-// struct PlayerSettings {
-//     ship_type: ShipType,
-//     name: String,
-// }
+/// Whether a run is live or paused, only exists while AppState::Playing
+///
+/// Rust Concept: Sub-states
+/// Pausing doesn't leave Playing, so nothing scoped to the run gets despawned
+#[derive(SubStates, Debug, Clone, PartialEq, Eq, Hash, Default)]
+#[source(AppState = AppState::Playing)]
+pub enum PlayState {
+    #[default]
+    Running,
+    Paused,
+}
+
+// Choices made before a run starts
+#[derive(Resource, Default)]
+pub struct PlayerSettings {
+    pub ship_type: ShipType,
+}
+
+/// Ship archetypes offered on the ship selection screen
+///
+/// Rust Concept: Enum methods as a data table
+/// Each variant answers for its own numbers
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum ShipType {
+    #[default]
+    SimpleShip,
+    Fighter,
+    Rocket,
+}
+
+impl ShipType {
+    pub const ALL: [ShipType; 3] = [Self::SimpleShip, Self::Fighter, Self::Rocket];
+
+    // cycle through ALL, wrapping at both ends
+    pub fn next(self) -> Self {
+        let i = Self::ALL.iter().position(|s| *s == self).unwrap_or(0);
+        Self::ALL[(i + 1) % Self::ALL.len()]
+    }
+
+    pub fn previous(self) -> Self {
+        let i = Self::ALL.iter().position(|s| *s == self).unwrap_or(0);
+        Self::ALL[(i + Self::ALL.len() - 1) % Self::ALL.len()]
+    }
+
+    pub fn display_name(&self) -> &'static str {
+        match self {
+            Self::SimpleShip => "Scout",
+            Self::Fighter => "Fighter",
+            Self::Rocket => "Rocket",
+        }
+    }
+
+    pub fn sprite_path(&self) -> &'static str {
+        match self {
+            Self::SimpleShip => "sprites/ship_G.png",
+            Self::Fighter => "player_fighter.png",
+            Self::Rocket => "player_rocket.png",
+        }
+    }
+
+    pub fn sprite_size(&self) -> Vec2 {
+        match self {
+            Self::SimpleShip => Vec2::new(40.0, 40.0),
+            Self::Fighter => Vec2::new(48.0, 50.0),
+            Self::Rocket => Vec2::new(44.0, 44.0),
+        }
+    }
+
+    // physics shape, roughly following the sprite outline
+    pub fn collider(&self) -> Collider {
+        match self {
+            Self::SimpleShip => Collider::triangle(
+                Vec2::new(0.0, 20.0),
+                Vec2::new(-20.0, -15.0),
+                Vec2::new(20.0, -15.0),
+            ),
+            Self::Fighter => Collider::convex_hull(vec![
+                Vec2::new(0.0, 24.0),
+                Vec2::new(-24.0, -10.0),
+                Vec2::new(-10.0, -24.0),
+                Vec2::new(10.0, -24.0),
+                Vec2::new(24.0, -10.0),
+            ])
+            .expect("Failed to create convex hull for fighter"),
+            Self::Rocket => Collider::capsule(9.0, 26.0),
+        }
+    }
+
+    pub fn mass(&self) -> f32 {
+        match self {
+            Self::SimpleShip => 15.0,
+            Self::Fighter => 25.0,
+            Self::Rocket => 10.0,
+        }
+    }
+
+    pub fn max_health(&self) -> f32 {
+        match self {
+            Self::SimpleShip => 100.0,
+            Self::Fighter => 150.0,
+            Self::Rocket => 70.0,
+        }
+    }
+
+    // the Fighter's armour takes the edge off collisions, the Rocket's thin skin doesn't
+    pub fn resistances(&self) -> Resistances {
+        match self {
+            Self::SimpleShip => Resistances::default(),
+            Self::Fighter => Resistances {
+                impact: 0.25,
+                explosion: 0.25,
+                ..default()
+            },
+            Self::Rocket => Resistances {
+                impact: -0.25,
+                ..default()
+            },
+        }
+    }
+
+    // (left, right) thruster positions relative to the ship center
+    pub fn main_thrusters(&self) -> [Vec2; 2] {
+        match self {
+            Self::SimpleShip => [Vec2::new(-12.0, -15.0), Vec2::new(12.0, -15.0)],
+            Self::Fighter => [Vec2::new(-8.0, -24.0), Vec2::new(8.0, -24.0)],
+            Self::Rocket => [Vec2::new(-5.0, -22.0), Vec2::new(5.0, -22.0)],
+        }
+    }
+
+    pub fn reverse_thrusters(&self) -> [Vec2; 2] {
+        match self {
+            Self::SimpleShip => [Vec2::new(-10.0, 15.0), Vec2::new(10.0, 15.0)],
+            Self::Fighter => [Vec2::new(-18.0, 8.0), Vec2::new(18.0, 8.0)],
+            Self::Rocket => [Vec2::new(-8.0, 12.0), Vec2::new(8.0, 12.0)],
+        }
+    }
+
+    /// Handling and weapon numbers, scaled from the PhysicsConfig baseline
+    ///
+    /// Rust Concept: Struct update syntax
+    /// ..base fills in every field we don't override
+    pub fn stats(&self, config: &PhysicsConfig) -> ShipStats {
+        let base = ShipStats {
+            thruster_force: config.thruster_force,
+            rotation_torque: config.rotation_torque,
+            reverse_thrust_force: config.reverse_thrust_force,
+            projectile_speed: config.projectile_speed,
+            projectile_lifetime: config.projectile_lifetime,
+            projectile_size: Vec2::new(10.0, 20.0),
+            muzzle_offset: 30.0,
+        };
+
+        match self {
+            Self::SimpleShip => base,
+            // heavy and tough, hits hard up close
+            Self::Fighter => ShipStats {
+                thruster_force: base.thruster_force * 1.6,
+                rotation_torque: base.rotation_torque * 1.4,
+                projectile_speed: base.projectile_speed * 1.3,
+                projectile_lifetime: base.projectile_lifetime * 0.75,
+                projectile_size: Vec2::new(8.0, 16.0),
+                muzzle_offset: 34.0,
+                ..base
+            },
+            // fast and fragile, long skinny shots, turns like a bus
+            Self::Rocket => ShipStats {
+                rotation_torque: base.rotation_torque * 0.6,
+                reverse_thrust_force: base.reverse_thrust_force * 0.6,
+                projectile_speed: base.projectile_speed * 1.6,
+                projectile_lifetime: base.projectile_lifetime * 0.5,
+                projectile_size: Vec2::new(6.0, 24.0),
+                muzzle_offset: 32.0,
+                ..base
+            },
+        }
+    }
+}
+
+/// Number of entries kept in the high-score table
+pub const MAX_HIGH_SCORES: usize = 10;
+
+/// Longest name that can be typed on the GameOver screen
+pub const MAX_NAME_LENGTH: usize = 12;
+
+/// Top runs, best first. Loaded at startup and saved whenever a name is entered
+#[derive(Resource, Debug, Default, Clone, PartialEq)]
+pub struct HighScores {
+    pub scores: Vec<(String, u32)>, // name, score
+}
+
+impl HighScores {
+    // would this score earn a spot in the table
+    pub fn qualifies(&self, score: u32) -> bool {
+        score > 0
+            && (self.scores.len() < MAX_HIGH_SCORES
+                || self
+                    .scores
+                    .last()
+                    .is_some_and(|(_, lowest)| score > *lowest))
+    }
+
+    /// Insert a score in rank order, returning its 0-based rank if it made the table
+    pub fn insert(&mut self, name: String, score: u32) -> Option<usize> {
+        if !self.qualifies(score) {
+            return None;
+        }
+
+        // ties go below existing entries, first one to get there keeps the spot
+        let rank = self
+            .scores
+            .iter()
+            .position(|(_, existing)| score > *existing)
+            .unwrap_or(self.scores.len());
+        self.scores.insert(rank, (name, score));
+        self.scores.truncate(MAX_HIGH_SCORES);
+        Some(rank)
+    }
+}
+
+/// Name being typed on the GameOver screen
+/// active is false when the run didn't make the table
+#[derive(Resource, Default)]
+pub struct NameEntry {
+    pub name: String,
+    pub active: bool,
+}
+
+/// Longest seed that can be typed on the menu
+pub const MAX_SEED_DIGITS: usize = 18;
+
+/// Find `--flag <value>` or `--flag=<value>` in the command line, the last one wins
+pub fn command_line_value(args: &[String], flag: &str) -> Option<String> {
+    let mut value = None;
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.strip_prefix(flag) {
+            Some("") => value = args.next().cloned(),
+            Some(rest) => {
+                if let Some(rest) = rest.strip_prefix('=') {
+                    value = Some(rest.to_string());
+                }
+            }
+            None => {}
+        }
+    }
+    value
+}
+
+/// Where the next run's seed comes from: `--seed <n>` on the command line or typed on the menu
+/// None means pick a fresh random seed every run
+#[derive(Resource, Default, Debug, PartialEq)]
+pub struct SeedSettings {
+    pub fixed_seed: Option<u64>,
+}
+
+impl SeedSettings {
+    /// Read `--seed <n>` or `--seed=<n>` from the command line
+    pub fn from_args(args: &[String]) -> Self {
+        let fixed_seed = command_line_value(args, "--seed").and_then(|value| match value.parse() {
+            Ok(seed) => Some(seed),
+            Err(_) => {
+                warn!("Ignoring bad --seed argument, expected a whole number");
+                None
+            }
+        });
+        Self { fixed_seed }
+    }
+
+    pub fn next_seed(&self) -> u64 {
+        // short random seeds are easier to read out to a teammate
+        self.fixed_seed
+            .unwrap_or_else(|| rand::rng().random_range(0..1_000_000_000))
+    }
+}
+
+/// All gameplay randomness for a run, derived from one seed
+///
+/// Rust Concept: Independent streams
+/// Each stream has its own generator, so pulling extra numbers from one
+/// (e.g. shooting a rock and splitting it) never shifts the others.
+/// The same seed always gives the same asteroid field.
+#[derive(Resource)]
+pub struct GameRng {
+    seed: u64,
+    pub spawning: StdRng,  // where, how big and how fast field asteroids are
+    pub shapes: StdRng,    // outlines and spin of field asteroids
+    pub fragments: StdRng, // everything about pieces split off by projectiles
+    pub cosmetic: StdRng,  // screen shake and other effects that don't affect play
+    pub pickups: StdRng,   // where and when power-ups show up
+}
+
+impl GameRng {
+    pub fn new(seed: u64) -> Self {
+        Self {
+            seed,
+            spawning: StdRng::seed_from_u64(seed),
+            shapes: StdRng::seed_from_u64(seed.wrapping_add(1)),
+            fragments: StdRng::seed_from_u64(seed.wrapping_add(2)),
+            cosmetic: StdRng::seed_from_u64(seed.wrapping_add(3)),
+            pickups: StdRng::seed_from_u64(seed.wrapping_add(4)),
+        }
+    }
+
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+}
+
+impl Default for GameRng {
+    fn default() -> Self {
+        Self::new(SeedSettings::default().next_seed())
+    }
+}
+
+/// Fixed simulation rate. Gameplay runs in FixedUpdate so a run can be replayed tick for tick
+pub const SIMULATION_HZ: f64 = 64.0;
+
+/// What the pilot is asking the ship to do, one snapshot per simulation tick
+///
+/// Everything that steers, fires or shows the thrusters reads this instead of the keyboard,
+/// so a replay can drive the exact same systems. Thruster strengths run from 0.0 (off) to
+/// 1.0 (full), keys always give full strength, analog triggers anything in between.
+#[derive(Resource, Default, Debug, Clone, Copy, PartialEq)]
+pub struct ShipControls {
+    pub left_thruster: f32,
+    pub right_thruster: f32,
+    pub reverse: f32,
+    pub fire: bool,          // fire is held right now
+    pub fire_pressed: bool,  // fire went down, held until the next simulation tick uses it
+    pub switch_weapon: bool, // same, for the switch weapon button
+}
+
+impl ShipControls {
+    /// Thrust strengths are stored in this many steps so a replay can save them exactly
+    pub const THRUST_STEPS: f32 = 255.0;
+
+    /// Snap a raw 0..1 strength to the nearest step
+    pub fn quantize(strength: f32) -> f32 {
+        (strength.clamp(0.0, 1.0) * Self::THRUST_STEPS).round() / Self::THRUST_STEPS
+    }
+
+    pub fn is_thrusting(&self) -> bool {
+        self.left_thruster > 0.0 || self.right_thruster > 0.0 || self.reverse > 0.0
+    }
+}
+
+/// Something the pilot can do, each one bound to keys and gamepad buttons
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ShipAction {
+    TurnLeft,
+    TurnRight,
+    Thrust,
+    Reverse,
+    Fire,
+    SwitchWeapon,
+}
+
+impl ShipAction {
+    pub const ALL: [ShipAction; 6] = [
+        ShipAction::TurnLeft,
+        ShipAction::TurnRight,
+        ShipAction::Thrust,
+        ShipAction::Reverse,
+        ShipAction::Fire,
+        ShipAction::SwitchWeapon,
+    ];
 
-// #[derive(Debug, Default)]
-// pub enum ShipType {
-//     #[default]
-//     SimpleShip,
-// }
+    /// Name used in the bindings file
+    pub fn config_name(&self) -> &'static str {
+        match self {
+            ShipAction::TurnLeft => "turn_left",
+            ShipAction::TurnRight => "turn_right",
+            ShipAction::Thrust => "thrust",
+            ShipAction::Reverse => "reverse",
+            ShipAction::Fire => "fire",
+            ShipAction::SwitchWeapon => "switch_weapon",
+        }
+    }
+}
 
-// #[derive(Resource)]
-// struct HighScores {
-//     scores: Vec<(String, u32)>, // name, score
-// }
+/// The keys and gamepad buttons that trigger one action
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ActionBinding {
+    pub keys: Vec<KeyCode>,
+    pub buttons: Vec<GamepadButton>,
+}
+
+/// Which inputs trigger which ShipAction, loaded from (and saved to) the bindings file
+///
+/// Rust Concept: HashMap for lookup tables
+/// Every action is filled in by Default, so lookups can index without checking
+#[derive(Resource, Debug, Clone, PartialEq)]
+pub struct InputBindings {
+    pub actions: HashMap<ShipAction, ActionBinding>,
+}
+
+impl InputBindings {
+    pub fn binding(&self, action: ShipAction) -> &ActionBinding {
+        &self.actions[&action]
+    }
+}
+
+impl Default for InputBindings {
+    fn default() -> Self {
+        let binding = |keys: &[KeyCode], buttons: &[GamepadButton]| ActionBinding {
+            keys: keys.to_vec(),
+            buttons: buttons.to_vec(),
+        };
+
+        // the triggers are analog, so squeezing one gently turns gently
+        Self {
+            actions: HashMap::from([
+                (
+                    ShipAction::TurnLeft,
+                    binding(
+                        &[KeyCode::ArrowLeft, KeyCode::KeyA],
+                        &[GamepadButton::LeftTrigger2, GamepadButton::DPadLeft],
+                    ),
+                ),
+                (
+                    ShipAction::TurnRight,
+                    binding(
+                        &[KeyCode::ArrowRight, KeyCode::KeyD],
+                        &[GamepadButton::RightTrigger2, GamepadButton::DPadRight],
+                    ),
+                ),
+                (
+                    ShipAction::Thrust,
+                    binding(&[KeyCode::ArrowUp, KeyCode::KeyW], &[GamepadButton::DPadUp]),
+                ),
+                (
+                    ShipAction::Reverse,
+                    binding(
+                        &[KeyCode::ArrowDown, KeyCode::KeyS],
+                        &[GamepadButton::DPadDown, GamepadButton::LeftTrigger],
+                    ),
+                ),
+                (
+                    ShipAction::Fire,
+                    binding(
+                        &[KeyCode::Space],
+                        &[GamepadButton::South, GamepadButton::RightTrigger],
+                    ),
+                ),
+                (
+                    ShipAction::SwitchWeapon,
+                    binding(&[KeyCode::KeyQ, KeyCode::Tab], &[GamepadButton::North]),
+                ),
+            ]),
+        }
+    }
+}
+
+/// A recorded run: the seed, ship and tuning it started from plus every tick's controls
+#[derive(Debug, Clone, PartialEq)]
+pub struct Replay {
+    pub seed: u64,
+    pub ship_type: ShipType,
+    pub timestep_hz: f64,
+    pub tuning: u64, // fingerprint of the config and campaign it was flown with
+    pub inputs: Vec<ShipControls>,
+}
+
+impl Replay {
+    pub fn new(seed: u64, ship_type: ShipType, timestep_hz: f64, tuning: u64) -> Self {
+        Self {
+            seed,
+            ship_type,
+            timestep_hz,
+            tuning,
+            inputs: Vec::new(),
+        }
+    }
+}
+
+/// Whether the current run is flown live (and recorded) or played back
+#[derive(Resource, Default)]
+pub enum RunMode {
+    #[default]
+    Live,
+    Replay {
+        replay: Replay,
+        tick: usize,
+    },
+}
+
+impl RunMode {
+    pub fn is_replay(&self) -> bool {
+        matches!(self, Self::Replay { .. })
+    }
+}
+
+/// Controls of the live run so far, saved as the last replay when it ends
+#[derive(Resource)]
+pub struct InputRecording(pub Replay);
+
+/// Replay that can be watched from the menu, the last run or one passed with --replay
+#[derive(Resource, Default)]
+pub struct LoadedReplay(pub Option<Replay>);
+
+/// Set the first time a finger touches the screen, from then on the on-screen controls show
+#[derive(Resource, Default)]
+pub struct TouchControlsEnabled(pub bool);
 
 #[derive(Resource)]
 pub struct ScreenShake {
@@ -64,7 +560,8 @@ pub struct SpawnTimer {
 }
 
 // Difficulty config for spawn timer
-#[derive(Resource)]
+#[derive(Resource, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
 pub struct DifficultyConfig {
     pub initial_interval: f32,
     pub min_interval: f32,
@@ -90,7 +587,6 @@ impl DifficultyConfig {
     }
 }
 
-// TODO i think increasing spawn rate as score goes up is a good idea
 impl Default for SpawnTimer {
     fn default() -> Self {
         Self {
@@ -101,28 +597,466 @@ impl Default for SpawnTimer {
     }
 }
 
-// Asteroid Spawning config
+/// Designer-set bounds for the difficulty director
+/// Each (easiest, hardest) pair is what the director uses at intensity 0 and 1
+#[derive(Resource, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct DirectorConfig {
+    pub enabled: bool,
+    pub window: f32,   // seconds of play the averages look back over
+    pub response: f32, // how quickly intensity follows the player, per second
+    pub start_intensity: f32,
+    // what a player who's coping exactly right looks like
+    pub target_kills_per_minute: f32,
+    pub target_accuracy: f32,
+    pub damage_tolerance: f32, // damage per second that counts as struggling
+    // multipliers on the time between spawns, asteroid speeds and spawning.size_bias
+    // (a size bias below 1 means bigger rocks)
+    pub interval_scale: (f32, f32),
+    pub speed_scale: (f32, f32),
+    pub size_bias_scale: (f32, f32),
+}
+
+impl Default for DirectorConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            window: 20.0,
+            response: 0.1,
+            start_intensity: 0.3,
+            target_kills_per_minute: 30.0,
+            target_accuracy: 0.4,
+            damage_tolerance: 2.0,
+            interval_scale: (1.5, 0.6),
+            speed_scale: (0.8, 1.3),
+            size_bias_scale: (1.3, 0.7),
+        }
+    }
+}
+
+/// The difficulty director's read on the player, and what it's doing about it
+///
+/// Rust Concept: Exponential moving averages
+/// Each tick nudges an average toward the latest value by dt / window, so it
+/// remembers roughly the last window seconds without keeping a history
 #[derive(Resource)]
+pub struct Director {
+    pub kills_per_minute: f32,
+    pub shots_per_second: f32,
+    pub hits_per_second: f32,
+    pub damage_per_second: f32,
+    pub health: f32,      // fraction of the ship's health left
+    pub performance: f32, // -1 is overwhelmed, 1 is bored
+    pub intensity: f32,   // 0 is the easiest the designer allows, 1 the hardest
+    // what the spawners use, worked out from intensity
+    pub interval_scale: f32,
+    pub speed_scale: f32,
+    pub size_bias_scale: f32,
+    pub shots_fired: u32, // trigger pulls since the last tick, counted by player_fire
+}
+
+/// What happened to the player during one tick
+#[derive(Debug, Clone, Copy, Default)]
+pub struct DirectorSample {
+    pub kills: u32,
+    pub shots: u32,
+    pub hits: f32, // a spread shot's pellets each count for their share of the pull
+    pub damage: f32,
+    pub health: f32, // fraction left at the end of the tick
+}
+
+impl Default for Director {
+    fn default() -> Self {
+        Self::new(&DirectorConfig::default())
+    }
+}
+
+impl Director {
+    pub fn new(config: &DirectorConfig) -> Self {
+        let mut director = Self {
+            kills_per_minute: config.target_kills_per_minute,
+            shots_per_second: 0.0,
+            hits_per_second: 0.0,
+            damage_per_second: 0.0,
+            health: 1.0,
+            performance: 0.0,
+            intensity: config.start_intensity,
+            interval_scale: 1.0,
+            speed_scale: 1.0,
+            size_bias_scale: 1.0,
+            shots_fired: 0,
+        };
+        director.apply(config);
+        director
+    }
+
+    /// Hits over shots, the target until enough shots have been fired to tell
+    pub fn accuracy(&self, config: &DirectorConfig) -> f32 {
+        if self.shots_per_second < 0.05 {
+            config.target_accuracy
+        } else {
+            (self.hits_per_second / self.shots_per_second).min(1.0)
+        }
+    }
+
+    /// Fold one tick of play into the averages and move intensity toward the player's level
+    pub fn observe(&mut self, config: &DirectorConfig, dt: f32, tick: DirectorSample) {
+        if dt <= 0.0 {
+            return;
+        }
+        let blend = (dt / config.window).min(1.0);
+        let average = |old: f32, new: f32| old + (new - old) * blend;
+        self.kills_per_minute = average(self.kills_per_minute, tick.kills as f32 / dt * 60.0);
+        self.shots_per_second = average(self.shots_per_second, tick.shots as f32 / dt);
+        self.hits_per_second = average(self.hits_per_second, tick.hits / dt);
+        self.damage_per_second = average(self.damage_per_second, tick.damage / dt);
+        self.health = tick.health;
+
+        // each signal is 0 when the player is right on target, positive when it's too easy
+        let signals = [
+            self.kills_per_minute / config.target_kills_per_minute - 1.0,
+            self.accuracy(config) / config.target_accuracy - 1.0,
+            1.0 - self.damage_per_second / config.damage_tolerance,
+            self.health * 2.0 - 1.0,
+        ];
+        self.performance =
+            signals.iter().map(|s| s.clamp(-1.0, 1.0)).sum::<f32>() / signals.len() as f32;
+
+        // intensity drifts toward where the player is coping, never jumps
+        let target = (self.performance + 1.0) / 2.0;
+        self.intensity += (target - self.intensity) * (config.response * dt).min(1.0);
+        self.apply(config);
+    }
+
+    // turn intensity into the numbers the spawners use
+    fn apply(&mut self, config: &DirectorConfig) {
+        if !config.enabled {
+            (self.interval_scale, self.speed_scale, self.size_bias_scale) = (1.0, 1.0, 1.0);
+            return;
+        }
+        let between = |(easiest, hardest): (f32, f32)| {
+            easiest + (hardest - easiest) * self.intensity.clamp(0.0, 1.0)
+        };
+        self.interval_scale = between(config.interval_scale);
+        self.speed_scale = between(config.speed_scale);
+        self.size_bias_scale = between(config.size_bias_scale);
+    }
+}
+
+/// Where the run is in the campaign
+///
+/// Rust Concept: State as data instead of States
+/// Levels and intermissions come and go inside the fixed-tick simulation, not as a
+/// Bevy state change, so a replay reaches every level on exactly the same tick
+#[derive(Resource, Default)]
+pub struct LevelProgress {
+    pub level: usize, // index into the campaign's levels
+    pub phase: LevelPhase,
+    pub elapsed: f32, // seconds into the current level or intermission
+    pub asteroids_destroyed: u32,
+    pub score_at_start: u32,
+    pub summary: Option<LevelSummary>, // the level just cleared, shown during the intermission
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum LevelPhase {
+    #[default]
+    Playing,
+    Intermission,
+    Endless, // out of levels, the spawn timer takes over
+}
+
+/// How the player did on a level
+#[derive(Debug, Clone)]
+pub struct LevelSummary {
+    pub name: String,
+    pub time: f32,
+    pub asteroids_destroyed: u32,
+    pub score: u32,
+    pub hull: f32, // fraction of the ship's health left
+}
+
+/// A side of the screen asteroids can come in from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Default)]
+pub enum ScreenEdge {
+    #[default]
+    Top,
+    Bottom,
+    Left,
+    Right,
+}
+
+impl ScreenEdge {
+    /// Straight into the screen from this edge
+    pub fn inward(&self) -> Vec2 {
+        match self {
+            ScreenEdge::Top => Vec2::NEG_Y,
+            ScreenEdge::Bottom => Vec2::Y,
+            ScreenEdge::Left => Vec2::X,
+            ScreenEdge::Right => Vec2::NEG_X,
+        }
+    }
+}
+
+// Asteroid Spawning config
+// Rocks come in on a rectangle just outside the screen: the top and bottom edges
+// at ±spawn_y, the sides at ±spawn_x
+#[derive(Resource, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
 pub struct AsteroidSpawnConfig {
+    pub min_radius: f32,
+    pub max_radius: f32,
+    pub size_bias: f32, // above 1 makes small rocks more common, 1 is even
     pub min_speed: f32,
     pub max_speed: f32,
     pub spawn_y: f32,
-    pub spawn_x_range: f32,
+    pub spawn_x_range: f32, // how far along the top and bottom edges
+    pub spawn_x: f32,
+    pub spawn_y_range: f32,            // how far along the side edges
+    pub edges: Vec<(ScreenEdge, u32)>, // how often each edge is used
+    pub aim_chance: f32,               // chance a rock is thrown at the ship
+    pub aim_lead: f32, // 0 aims where the ship is, 1 where it'll be when the rock arrives
+    pub aim_spread: f32, // degrees either side, so aimed rocks can still miss
+    // this far past the spawn lines a rock is gone, whichever way it's heading
+    pub cleanup_distance: f32,
 }
 
 impl Default for AsteroidSpawnConfig {
     fn default() -> Self {
         Self {
+            min_radius: 12.0,
+            max_radius: 50.0,
+            size_bias: 1.5,
             min_speed: 100.0,
             max_speed: 250.0,
             spawn_x_range: 370.0,
             spawn_y: 320.0,
+            spawn_x: 420.0,
+            spawn_y_range: 270.0,
+            edges: vec![
+                (ScreenEdge::Top, 70),
+                (ScreenEdge::Left, 12),
+                (ScreenEdge::Right, 12),
+                (ScreenEdge::Bottom, 6),
+            ],
+            aim_chance: 0.25,
+            aim_lead: 0.5,
+            aim_spread: 10.0,
+            cleanup_distance: 500.0,
         }
     }
 }
 
-// Physics configuration resource
+impl AsteroidSpawnConfig {
+    pub fn pick_edge(&self, rng: &mut impl Rng) -> ScreenEdge {
+        pick_weighted(&self.edges, rng).unwrap_or_default()
+    }
+
+    /// A point on one of the spawn lines, `along` runs from -1 to 1
+    pub fn edge_point(&self, edge: ScreenEdge, along: f32) -> Vec2 {
+        match edge {
+            ScreenEdge::Top => Vec2::new(along * self.spawn_x_range, self.spawn_y),
+            ScreenEdge::Bottom => Vec2::new(along * self.spawn_x_range, -self.spawn_y),
+            ScreenEdge::Left => Vec2::new(-self.spawn_x, along * self.spawn_y_range),
+            ScreenEdge::Right => Vec2::new(self.spawn_x, along * self.spawn_y_range),
+        }
+    }
+
+    /// Which way to throw a rock from `from` at `speed` so it meets the ship
+    /// None when the rock is already on top of it
+    pub fn aim(
+        &self,
+        from: Vec2,
+        speed: f32,
+        target_position: Vec2,
+        target_velocity: Vec2,
+    ) -> Option<Vec2> {
+        // Rust Concept: Leading a moving target
+        // One step is close enough: guess the travel time from the distance now
+        // and aim at where the ship will have drifted to by then
+        let travel_time = from.distance(target_position) / speed;
+        let aim_point = target_position + target_velocity * travel_time * self.aim_lead;
+        (aim_point - from).try_normalize()
+    }
+
+    /// Whether something has left the play area for good
+    /// Past a spawn line and heading further out, or just too far away
+    pub fn is_gone(&self, position: Vec2, velocity: Vec2) -> bool {
+        let outside = position.abs() - Vec2::new(self.spawn_x, self.spawn_y);
+        // positive on an axis where it's moving away from the middle
+        let heading_out = position.signum() * velocity;
+
+        (outside.x > 0.0 && heading_out.x >= 0.0)
+            || (outside.y > 0.0 && heading_out.y >= 0.0)
+            || outside.max_element() > self.cleanup_distance
+    }
+}
+
+/// How an asteroid's numbers follow from its size, see AsteroidSize
+#[derive(Resource, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct AsteroidTuning {
+    pub density: f32,                  // mass for every unit of area (square pixel)
+    pub hit_points_per_mass: f32,      // toughness
+    pub impact_energy_per_damage: f32, // collision energy it takes to do a point of damage
+    pub min_impact_speed: f32,         // closing speeds below this only scrape the hull
+    pub max_impact_damage: f32,        // the most one collision can do
+    pub min_split_radius: f32,         // smaller rocks crumble instead of splitting
+    pub score_scale: f32,              // score is this over the radius
+}
+
+impl Default for AsteroidTuning {
+    fn default() -> Self {
+        Self {
+            density: 0.005,
+            hit_points_per_mass: 1.4, // a small rock still breaks to a single blaster shot
+            impact_energy_per_damage: 7000.0, // a mid-sized rock at 200 px/s does about 20
+            min_impact_speed: 40.0,
+            max_impact_damage: 60.0,
+            min_split_radius: 20.0,
+            score_scale: 1500.0,
+        }
+    }
+}
+
+impl AsteroidTuning {
+    /// Damage to the ship from two bodies meeting at `closing_speed`
+    ///
+    /// Rust Concept: Reduced mass
+    /// Only the energy of the two bodies moving towards each other goes into the
+    /// crunch, in their shared centre-of-mass frame that's ½μv² with
+    /// μ = m1·m2 / (m1 + m2). A pebble barely dents a heavy ship, a boulder doesn't.
+    pub fn impact_damage(&self, reduced_mass: f32, closing_speed: f32) -> f32 {
+        if closing_speed < self.min_impact_speed {
+            return 0.0;
+        }
+        let energy = 0.5 * reduced_mass * closing_speed * closing_speed;
+        (energy / self.impact_energy_per_damage).min(self.max_impact_damage)
+    }
+}
+
+/// μ = m1·m2 / (m1 + m2), see AsteroidTuning::impact_damage
+pub fn reduced_mass(mass1: f32, mass2: f32) -> f32 {
+    mass1 * mass2 / (mass1 + mass2)
+}
+
+/// Which silhouettes field asteroids get, and how often
+/// Each kind comes up with a chance of its weight over the total
+#[derive(Resource, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ShapeRegistry {
+    pub weights: Vec<(ShapeKind, u32)>,
+}
+
+impl ShapeRegistry {
+    pub fn pick(&self, rng: &mut impl Rng) -> ShapeKind {
+        pick_weighted(&self.weights, rng).unwrap_or(ShapeKind::Irregular)
+    }
+}
+
+impl Default for ShapeRegistry {
+    fn default() -> Self {
+        Self {
+            weights: vec![
+                (ShapeKind::Irregular, 35),
+                (ShapeKind::Lumpy, 20),
+                (ShapeKind::Potato, 20),
+                (ShapeKind::Rounded, 15),
+                (ShapeKind::Crystal, 10),
+            ],
+        }
+    }
+}
+
+/// How often field asteroids come in each material
+/// Each material comes up with a chance of its weight over the total
+#[derive(Resource, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct MaterialRegistry {
+    pub weights: Vec<(AsteroidMaterial, u32)>,
+}
+
+impl MaterialRegistry {
+    pub fn pick(&self, rng: &mut impl Rng) -> AsteroidMaterial {
+        pick_weighted(&self.weights, rng).unwrap_or_default()
+    }
+}
+
+impl Default for MaterialRegistry {
+    fn default() -> Self {
+        Self {
+            weights: vec![
+                (AsteroidMaterial::Rock, 60),
+                (AsteroidMaterial::Ice, 12),
+                (AsteroidMaterial::Iron, 12),
+                (AsteroidMaterial::Explosive, 8),
+                (AsteroidMaterial::Crystal, 8),
+            ],
+        }
+    }
+}
+
+/// Pick one of the options, each with a chance of its weight over the total
+/// None if every weight is zero
+///
+/// Rust Concept: Weighted random choice
+/// Roll once over the total, then walk the list until the roll lands in a weight
+pub fn pick_weighted<T: Copy>(weights: &[(T, u32)], rng: &mut impl Rng) -> Option<T> {
+    let total: u32 = weights.iter().map(|(_, weight)| weight).sum();
+    if total == 0 {
+        return None;
+    }
+
+    let mut roll = rng.random_range(0..total);
+    for (option, weight) in weights {
+        if roll < *weight {
+            return Some(*option);
+        }
+        roll -= weight;
+    }
+    unreachable!("the roll is always below the total")
+}
+
+/// Power-up tuning: how often they drift in and what the shield is worth
 #[derive(Resource)]
+pub struct PowerUpConfig {
+    pub spawn_interval: f32,
+    pub speed: f32,
+    pub radius: f32,
+    pub shield_hit_points: f32,
+    pub shield_duration: f32,
+    pub crystal_bonus: u32, // points for the gem a crystal asteroid leaves behind
+}
+
+impl Default for PowerUpConfig {
+    fn default() -> Self {
+        Self {
+            spawn_interval: 15.0,
+            speed: 80.0,
+            radius: 16.0,
+            shield_hit_points: 40.0, // a couple of small rocks or one large one
+            shield_duration: 12.0,
+            crystal_bonus: 250,
+        }
+    }
+}
+
+/// Counts down to the next power-up, reset with every run
+#[derive(Resource)]
+pub struct PowerUpTimer(pub Timer);
+
+impl Default for PowerUpTimer {
+    fn default() -> Self {
+        Self(Timer::from_seconds(
+            PowerUpConfig::default().spawn_interval,
+            TimerMode::Repeating,
+        ))
+    }
+}
+
+// Physics configuration resource
+#[derive(Resource, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
 pub struct PhysicsConfig {
     pub thruster_force: f32,
     pub rotation_torque: f32,
@@ -147,6 +1081,27 @@ impl Default for PhysicsConfig {
     }
 }
 
+/// How the ship takes damage, beyond how hard each hit is
+#[derive(Resource, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct DamageConfig {
+    pub invulnerability: f32, // seconds the ship ignores all damage for after a hit
+    pub blink_rate: f32,      // blinks per second while it does
+    pub contact_damage_per_second: f32, // for staying pressed against a rock
+    pub repair_per_level: f32, // hull patched up after clearing a level
+}
+
+impl Default for DamageConfig {
+    fn default() -> Self {
+        Self {
+            invulnerability: 1.0,
+            blink_rate: 8.0,
+            contact_damage_per_second: 10.0,
+            repair_per_level: 20.0,
+        }
+    }
+}
+
 /// Display player score (simple text for now)
 ///
 /// Rust Concept: One-shot systems for UI setup
@@ -197,10 +1152,13 @@ pub(crate) struct AudioAssets {
     pub game_start: Handle<AudioSource>,
     pub game_over: Handle<AudioSource>,
     pub menu_boop: Handle<AudioSource>,
+    pub shield_up: Handle<AudioSource>,
+    pub shield_hit: Handle<AudioSource>,
+    pub shield_down: Handle<AudioSource>,
 }
 
 impl AudioAssets {
-    pub fn handles(&self) -> [Handle<AudioSource>; 7] {
+    pub fn handles(&self) -> [Handle<AudioSource>; 10] {
         [
             self.thruster.clone(),
             self.explosion.clone(),
@@ -209,6 +1167,166 @@ impl AudioAssets {
             self.game_start.clone(),
             self.game_over.clone(),
             self.menu_boop.clone(),
+            self.shield_up.clone(),
+            self.shield_hit.clone(),
+            self.shield_down.clone(),
         ]
     }
 }
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use crate::components::AsteroidSize;
+    use std::f32::consts::PI;
+
+    /// The masses of the starter ship and a mid-sized rock, the usual collision to tune against
+    pub(crate) fn starter_ship_and_mid_sized_rock() -> (f32, f32) {
+        let rock = AsteroidSize {
+            area: PI * 30.0 * 30.0,
+        };
+        (
+            ShipType::SimpleShip.mass(),
+            rock.mass(&AsteroidTuning::default()),
+        )
+    }
+
+    #[test]
+    fn test_shape_registry_follows_weights() {
+        let registry = ShapeRegistry {
+            weights: vec![(ShapeKind::Lumpy, 0), (ShapeKind::Crystal, 3)],
+        };
+        let mut rng = StdRng::seed_from_u64(1);
+        assert!((0..50).all(|_| registry.pick(&mut rng) == ShapeKind::Crystal));
+    }
+
+    #[test]
+    fn test_harder_hits_hurt_more() {
+        let tuning = AsteroidTuning::default();
+        let (ship, rock) = starter_ship_and_mid_sized_rock();
+        let reduced_mass = reduced_mass(ship, rock);
+
+        assert_eq!(tuning.impact_damage(reduced_mass, 20.0), 0.0);
+        let glancing = tuning.impact_damage(reduced_mass, 100.0);
+        let head_on = tuning.impact_damage(reduced_mass, 200.0);
+        assert!(glancing > 0.0 && (head_on / glancing - 4.0).abs() < 1e-3);
+        assert!(head_on > 15.0 && head_on < 25.0, "{head_on}");
+        assert_eq!(
+            tuning.impact_damage(reduced_mass, 2000.0),
+            tuning.max_impact_damage
+        );
+    }
+
+    #[test]
+    fn test_rocks_leave_from_every_side() {
+        let config = AsteroidSpawnConfig::default();
+
+        // just spawned on any edge and heading in
+        for edge in [
+            ScreenEdge::Top,
+            ScreenEdge::Bottom,
+            ScreenEdge::Left,
+            ScreenEdge::Right,
+        ] {
+            let point = config.edge_point(edge, 0.5);
+            assert!(!config.is_gone(point, edge.inward() * 100.0), "{edge:?}");
+            assert!(
+                config.is_gone(point - edge.inward(), -edge.inward() * 100.0),
+                "{edge:?}"
+            );
+        }
+
+        // bounced back up off the ship
+        assert!(config.is_gone(Vec2::new(0.0, 400.0), Vec2::new(20.0, 80.0)));
+        // a formation still coming in from high above
+        assert!(!config.is_gone(Vec2::new(0.0, 600.0), Vec2::new(0.0, -80.0)));
+    }
+
+    #[test]
+    fn test_aiming_leads_the_ship() {
+        let config = AsteroidSpawnConfig {
+            aim_lead: 1.0,
+            ..default()
+        };
+        let from = Vec2::new(0.0, 300.0);
+
+        let still = config.aim(from, 100.0, Vec2::ZERO, Vec2::ZERO).unwrap();
+        assert!(still.abs_diff_eq(Vec2::NEG_Y, 1e-5));
+
+        // 3 seconds away from a ship heading right at 50, aim 150 to the right
+        let moving = config
+            .aim(from, 100.0, Vec2::ZERO, Vec2::new(50.0, 0.0))
+            .unwrap();
+        assert!(moving.abs_diff_eq(Vec2::new(150.0, -300.0).normalize(), 1e-5));
+    }
+
+    #[test]
+    fn test_director_follows_the_player() {
+        let config = DirectorConfig::default();
+        let tick = 1.0 / 64.0;
+
+        // taking a beating and hitting nothing
+        let mut struggling = Director::new(&config);
+        for _ in 0..64 * 30 {
+            let sample = DirectorSample {
+                shots: 1,
+                damage: 0.2,
+                health: 0.2,
+                ..default()
+            };
+            struggling.observe(&config, tick, sample);
+        }
+        assert!(struggling.intensity < config.start_intensity);
+        assert!(struggling.interval_scale > 1.0, "fewer rocks");
+
+        // a kill a second without a scratch
+        let mut cruising = Director::new(&config);
+        for i in 0..64 * 30 {
+            let sample = DirectorSample {
+                kills: u32::from(i % 64 == 0),
+                shots: u32::from(i % 32 == 0),
+                hits: if i % 32 == 0 { 1.0 } else { 0.0 },
+                health: 1.0,
+                ..default()
+            };
+            cruising.observe(&config, tick, sample);
+        }
+        assert!(cruising.intensity > config.start_intensity);
+        assert!(cruising.speed_scale > struggling.speed_scale);
+        // intensity never leaves the designer's bounds
+        assert!(cruising.interval_scale >= config.interval_scale.1);
+    }
+
+    #[test]
+    fn test_seed_from_args() {
+        fn args(list: &[&str]) -> Vec<String> {
+            list.iter().map(|s| s.to_string()).collect()
+        }
+
+        assert_eq!(SeedSettings::from_args(&args(&["game"])).fixed_seed, None);
+        assert_eq!(
+            SeedSettings::from_args(&args(&["game", "--seed", "42"])).fixed_seed,
+            Some(42)
+        );
+        assert_eq!(
+            SeedSettings::from_args(&args(&["game", "--seed=7"])).fixed_seed,
+            Some(7)
+        );
+        assert_eq!(
+            SeedSettings::from_args(&args(&["game", "--seed", "abc"])).fixed_seed,
+            None
+        );
+    }
+
+    #[test]
+    fn test_same_seed_same_streams() {
+        let mut a = GameRng::new(1234);
+        let mut b = GameRng::new(1234);
+
+        // drawing from one stream must not move another
+        let _: u32 = a.fragments.random();
+        assert_eq!(a.spawning.random::<u64>(), b.spawning.random::<u64>());
+        assert_eq!(a.shapes.random::<u64>(), b.shapes.random::<u64>());
+        assert_eq!(a.seed(), 1234);
+    }
+}
@@ -0,0 +1,303 @@
+use super::polygon::{IrregularPolygonGenerator, ShapeGenerator, ensure_ccw};
+use bevy::prelude::*;
+use rand::Rng;
+use serde::Deserialize;
+use std::f32::consts::{PI, TAU};
+
+/// Smooth, lumpy outlines
+///
+/// The radius follows a few sine waves around the circle with random phases, a
+/// cheap 1D noise that wraps around seamlessly. Higher harmonics are weaker, so
+/// the rock gets a few big lumps and some smaller bumps on top.
+pub struct NoiseGenerator {
+    pub vertex_count: usize,
+    pub base_radius: f32,
+    pub roughness: f32, // 0 is a circle, keep below 0.5 so the radius never goes negative
+    pub harmonics: usize,
+}
+
+impl NoiseGenerator {
+    pub fn new(vertex_count: usize, base_radius: f32) -> Self {
+        Self {
+            vertex_count,
+            base_radius,
+            roughness: 0.35,
+            harmonics: 4,
+        }
+    }
+}
+
+impl ShapeGenerator for NoiseGenerator {
+    fn generate(&self, rng: &mut impl Rng) -> Vec<Vec2> {
+        // (frequency, strength, phase) for each wave, starting from two lumps
+        let waves: Vec<(f32, f32, f32)> = (2..2 + self.harmonics)
+            .map(|frequency| {
+                let strength = rng.random_range(0.5..1.0) / frequency as f32;
+                (frequency as f32, strength, rng.random_range(0.0..TAU))
+            })
+            .collect();
+        let total_strength: f32 = waves.iter().map(|(_, strength, _)| strength).sum();
+
+        (0..self.vertex_count)
+            .map(|i| {
+                let angle = i as f32 / self.vertex_count as f32 * TAU;
+                let noise: f32 = waves
+                    .iter()
+                    .map(|(frequency, strength, phase)| {
+                        strength * (frequency * angle + phase).sin()
+                    })
+                    .sum();
+                // noise is now within -1..1
+                let radius = self.base_radius * (1.0 + self.roughness * noise / total_strength);
+                Vec2::from_angle(angle) * radius
+            })
+            .collect()
+    }
+}
+
+/// Shards: a handful of sharp crystal points with deep valleys between them
+pub struct CrystalGenerator {
+    pub point_count: usize,
+    pub base_radius: f32,
+    pub valley_depth: f32, // valleys sit this fraction of the way in from the points
+}
+
+impl CrystalGenerator {
+    pub fn new(point_count: usize, base_radius: f32) -> Self {
+        Self {
+            point_count,
+            base_radius,
+            valley_depth: 0.45,
+        }
+    }
+}
+
+impl ShapeGenerator for CrystalGenerator {
+    fn generate(&self, rng: &mut impl Rng) -> Vec<Vec2> {
+        let step = TAU / self.point_count as f32;
+
+        // Rust Concept: flat_map
+        // Every point expands to two vertices: its tip and the valley after it
+        (0..self.point_count)
+            .flat_map(|i| {
+                let angle = i as f32 * step + rng.random_range(-0.2..0.2) * step;
+                let tip = self.base_radius * rng.random_range(0.9..1.3);
+                let valley =
+                    self.base_radius * (1.0 - self.valley_depth) * rng.random_range(0.8..1.1);
+                let valley_angle = angle + step * rng.random_range(0.4..0.6);
+                [
+                    Vec2::from_angle(angle) * tip,
+                    Vec2::from_angle(valley_angle) * valley,
+                ]
+            })
+            .collect()
+    }
+}
+
+/// Elongated potato shapes: a wobbly ellipse at a random angle
+pub struct PotatoGenerator {
+    pub vertex_count: usize,
+    pub base_radius: f32,
+    pub aspect_ratio: f32, // long axis over short axis
+    pub radius_variation: f32,
+}
+
+impl PotatoGenerator {
+    pub fn new(vertex_count: usize, base_radius: f32, aspect_ratio: f32) -> Self {
+        Self {
+            vertex_count,
+            base_radius,
+            aspect_ratio,
+            radius_variation: 0.1,
+        }
+    }
+}
+
+impl ShapeGenerator for PotatoGenerator {
+    fn generate(&self, rng: &mut impl Rng) -> Vec<Vec2> {
+        // stretch one way and squash the other so the area matches a circle of base_radius
+        let stretch = self.aspect_ratio.max(1.0).sqrt();
+        let axes = Vec2::new(self.base_radius * stretch, self.base_radius / stretch);
+        let rotation = Vec2::from_angle(rng.random_range(0.0..PI));
+
+        (0..self.vertex_count)
+            .map(|i| {
+                let angle = i as f32 / self.vertex_count as f32 * TAU;
+                let wobble =
+                    rng.random_range(1.0 - self.radius_variation..1.0 + self.radius_variation);
+                rotation.rotate(Vec2::from_angle(angle) * axes * wobble)
+            })
+            .collect()
+    }
+}
+
+/// Any other generator's outline with its corners rounded off
+///
+/// Rust Concept: Minkowski sum
+/// Sweeping a circle around the outline pushes every edge out by the radius and
+/// turns every outward corner into an arc, like a pebble worn smooth.
+/// Generic over the wrapped generator, so it can round any of them.
+pub struct RoundedGenerator<G: ShapeGenerator> {
+    pub inner: G,
+    pub rounding: f32, // radius of the swept circle
+    pub arc_step: f32, // angle between vertices on each rounded corner
+}
+
+impl<G: ShapeGenerator> RoundedGenerator<G> {
+    pub fn new(inner: G, rounding: f32) -> Self {
+        Self {
+            inner,
+            rounding,
+            arc_step: 0.4,
+        }
+    }
+}
+
+impl<G: ShapeGenerator> ShapeGenerator for RoundedGenerator<G> {
+    fn generate(&self, rng: &mut impl Rng) -> Vec<Vec2> {
+        let mut core = self.inner.generate(rng);
+        ensure_ccw(&mut core);
+        let count = core.len();
+        let mut rounded = Vec::with_capacity(count * 3);
+
+        for i in 0..count {
+            let (prev, corner, next) = (
+                core[(i + count - 1) % count],
+                core[i],
+                core[(i + 1) % count],
+            );
+            // outward normals of the edges either side of the corner
+            let normal_in = -(corner - prev).perp().normalize_or_zero();
+            let normal_out = -(next - corner).perp().normalize_or_zero();
+            let turn = normal_in.angle_to(normal_out);
+
+            if turn > 0.0 {
+                // an outward corner, sweep an arc between the two offset edges
+                let steps = (turn / self.arc_step).ceil().max(1.0) as usize;
+                rounded.extend((0..=steps).map(|step| {
+                    let normal =
+                        normal_in.rotate(Vec2::from_angle(turn * step as f32 / steps as f32));
+                    corner + normal * self.rounding
+                }));
+            } else {
+                // a dent, the offset edges meet at a single point
+                let bisector = normal_in + normal_out;
+                rounded.push(
+                    corner + bisector * self.rounding / (1.0 + normal_in.dot(normal_out)).max(0.1),
+                );
+            }
+        }
+
+        rounded
+    }
+}
+
+/// Every kind of silhouette a field asteroid can have
+///
+/// Rust Concept: Enum dispatch
+/// ShapeGenerator::generate is generic over the rng, so generators can't be
+/// boxed as trait objects. Instead each variant builds its concrete generator
+/// and calls it directly, which also keeps all the tuning in one place.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+pub enum ShapeKind {
+    Irregular,
+    Lumpy,
+    Crystal,
+    Potato,
+    Rounded,
+}
+
+/// Bigger rocks get more vertices so their outline stays as detailed
+fn vertex_count_for(radius: f32) -> usize {
+    ((radius * 0.6).round() as usize).clamp(8, 30)
+}
+
+impl ShapeKind {
+    pub const ALL: [ShapeKind; 5] = [
+        ShapeKind::Irregular,
+        ShapeKind::Lumpy,
+        ShapeKind::Crystal,
+        ShapeKind::Potato,
+        ShapeKind::Rounded,
+    ];
+
+    /// An outline of this kind with roughly the area of a circle of `radius`
+    pub fn generate(&self, radius: f32, rng: &mut impl Rng) -> Vec<Vec2> {
+        let vertex_count = vertex_count_for(radius);
+
+        match self {
+            // Rust Concept: Struct update syntax
+            // Take the defaults from new() and only override the crater settings
+            ShapeKind::Irregular => IrregularPolygonGenerator {
+                bite_chance: 0.12,
+                ..IrregularPolygonGenerator::new(vertex_count, radius)
+            }
+            .generate(rng),
+            ShapeKind::Lumpy => NoiseGenerator::new(vertex_count.max(16), radius).generate(rng),
+            ShapeKind::Crystal => {
+                // the valleys eat into the area, so the points reach further out
+                CrystalGenerator::new(rng.random_range(4..=7), radius * 1.3).generate(rng)
+            }
+            ShapeKind::Potato => {
+                let aspect_ratio = rng.random_range(1.5..2.5);
+                PotatoGenerator::new(vertex_count, radius, aspect_ratio).generate(rng)
+            }
+            ShapeKind::Rounded => {
+                // shrink the core so the rounding brings it back up to size
+                let rounding = radius * 0.3;
+                let core =
+                    IrregularPolygonGenerator::new(rng.random_range(5..=7), radius - rounding);
+                RoundedGenerator::new(core, rounding).generate(rng)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::shapes::geometry::signed_area;
+    use rand::SeedableRng;
+    use rand::rngs::StdRng;
+
+    #[test]
+    fn test_every_kind_makes_a_sensible_rock() {
+        let mut rng = StdRng::seed_from_u64(7);
+        let circle_area = PI * 30.0 * 30.0;
+
+        for kind in ShapeKind::ALL {
+            for _ in 0..20 {
+                let mut outline = kind.generate(30.0, &mut rng);
+                ensure_ccw(&mut outline);
+                let area = signed_area(&outline);
+                assert!(outline.len() >= 3, "{kind:?}");
+                assert!(
+                    area > circle_area * 0.4 && area < circle_area * 1.6,
+                    "{kind:?} area {area}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_rounding_a_square() {
+        struct Square;
+        impl ShapeGenerator for Square {
+            fn generate(&self, _rng: &mut impl Rng) -> Vec<Vec2> {
+                vec![
+                    Vec2::new(-1.0, -1.0),
+                    Vec2::new(1.0, -1.0),
+                    Vec2::new(1.0, 1.0),
+                    Vec2::new(-1.0, 1.0),
+                ]
+            }
+        }
+
+        let rounded = RoundedGenerator::new(Square, 1.0).generate(&mut StdRng::seed_from_u64(0));
+        // every vertex is exactly the rounding away from the square
+        for vertex in rounded {
+            let outside = (vertex.abs() - Vec2::ONE).max(Vec2::ZERO);
+            assert!((outside.length() - 1.0).abs() < 1e-4, "{vertex:?}");
+        }
+    }
+}
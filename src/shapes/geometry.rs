@@ -0,0 +1,85 @@
+use bevy::prelude::*;
+
+/// Area of a polygon using the shoelace formula, whichever way it winds
+pub fn polygon_area(vertices: &[Vec2]) -> f32 {
+    signed_area(vertices).abs()
+}
+
+/// Centre of mass of a polygon with even density
+///
+/// Each edge makes a triangle with the origin, the centre is their centres
+/// weighted by their (signed) areas
+pub fn polygon_centroid(vertices: &[Vec2]) -> Vec2 {
+    let area = signed_area(vertices);
+    if area.abs() < f32::EPSILON {
+        // degenerate, fall back to the average vertex
+        return vertices.iter().copied().sum::<Vec2>() / vertices.len().max(1) as f32;
+    }
+
+    let weighted = edges(vertices).fold(Vec2::ZERO, |sum, (a, b)| sum + (a + b) * a.perp_dot(b));
+    weighted / (6.0 * area)
+}
+
+/// Moment of inertia of a polygon with even density about its own centroid
+///
+/// This is how hard it is to spin up, the rotational version of mass. Like the
+/// centroid it's a sum over the triangles each edge makes with the origin, then
+/// moved from the origin to the centroid with the parallel axis theorem.
+///
+/// Rust Concept: Folding with a tuple accumulator
+/// One pass over the edges builds both sums at once
+pub fn polygon_moment_of_inertia(vertices: &[Vec2], mass: f32) -> f32 {
+    let (numerator, denominator) = edges(vertices).fold((0.0, 0.0), |(num, den), (a, b)| {
+        let cross = a.perp_dot(b);
+        (num + cross * (a.dot(a) + a.dot(b) + b.dot(b)), den + cross)
+    });
+    if denominator.abs() < f32::EPSILON {
+        return 0.0;
+    }
+
+    let about_origin = mass * numerator / (6.0 * denominator);
+    about_origin - mass * polygon_centroid(vertices).length_squared()
+}
+
+// every edge as (start, end), including the one that closes the loop
+pub(crate) fn edges(vertices: &[Vec2]) -> impl Iterator<Item = (Vec2, Vec2)> + '_ {
+    vertices
+        .iter()
+        .zip(vertices.iter().cycle().skip(1))
+        .map(|(a, b)| (*a, *b))
+}
+
+// positive for counter-clockwise
+pub(crate) fn signed_area(vertices: &[Vec2]) -> f32 {
+    edges(vertices).map(|(a, b)| a.perp_dot(b)).sum::<f32>() * 0.5
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn square_at(centre: Vec2) -> Vec<Vec2> {
+        [(-1.0, -1.0), (1.0, -1.0), (1.0, 1.0), (-1.0, 1.0)]
+            .into_iter()
+            .map(|(x, y)| centre + Vec2::new(x, y))
+            .collect()
+    }
+
+    #[test]
+    fn test_area_and_centroid() {
+        let square = square_at(Vec2::new(3.0, 2.0));
+        assert!((polygon_area(&square) - 4.0).abs() < 1e-5);
+        assert!(polygon_centroid(&square).distance(Vec2::new(3.0, 2.0)) < 1e-5);
+    }
+
+    #[test]
+    fn test_moment_of_inertia() {
+        // a 2x2 square: m * (w² + h²) / 12, wherever it sits
+        let expected = 6.0 * (4.0 + 4.0) / 12.0;
+        assert!((polygon_moment_of_inertia(&square_at(Vec2::ZERO), 6.0) - expected).abs() < 1e-4);
+        assert!(
+            (polygon_moment_of_inertia(&square_at(Vec2::new(5.0, -3.0)), 6.0) - expected).abs()
+                < 1e-3
+        );
+    }
+}
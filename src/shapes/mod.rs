@@ -1,8 +1,18 @@
+pub mod generators;
+pub mod geometry;
 pub mod polygon;
+pub mod slice;
 
+pub use generators::{
+    CrystalGenerator, NoiseGenerator, PotatoGenerator, RoundedGenerator, ShapeKind,
+};
+pub use geometry::{polygon_area, polygon_centroid, polygon_moment_of_inertia};
 pub use polygon::{
     ShapeGenerator,
     IrregularPolygonGenerator,
+    convex_decomposition,
     ensure_ccw,
     simplify_polygon,
+    triangulate,
 };
+pub use slice::slice_polygon;
@@ -12,12 +12,15 @@ generator for irregular polygons
 1. place vertices in a circle
 2. Randomly varying the radius for each vertex
 3. Adding a slight angular variation
+4. Optionally biting some vertices in deep, for concave craters
 */
 pub struct IrregularPolygonGenerator {
     pub vertex_count: usize,
     pub base_radius: f32,
     pub radius_variation: f32,
     pub angle_variation: f32,
+    pub bite_chance: f32, // chance each vertex is pulled in, 0 keeps the outline roughly convex
+    pub bite_depth: f32,  // fraction of the radius a bite takes out
 }
 
 impl IrregularPolygonGenerator {
@@ -27,6 +30,8 @@ impl IrregularPolygonGenerator {
             base_radius,
             radius_variation: 0.3, // 30% variation in radius
             angle_variation: 0.2,  //small angular jitter
+            bite_chance: 0.0,
+            bite_depth: 0.45,
         }
     }
 
@@ -36,13 +41,23 @@ impl IrregularPolygonGenerator {
         let base_angle = (index as f32 / self.vertex_count as f32) * 2.0 * PI;
 
         //add some random angular variation (range syntax for random generation)
-        let angle_offset = rng.random_range(-self.angle_variation..self.angle_variation);
+        // never more than half the gap to the next vertex, so the vertices stay in
+        // order around the centre and the outline can't cross itself
+        let max_offset = self
+            .angle_variation
+            .min(PI / self.vertex_count as f32 * 0.9);
+        let angle_offset = rng.random_range(-max_offset..max_offset);
         let angle = base_angle + angle_offset;
 
         // vary the radius randomly
         let radius_factor =
             rng.random_range(1.0 - self.radius_variation..1.0 + self.radius_variation);
-        let radius = self.base_radius * radius_factor;
+        let mut radius = self.base_radius * radius_factor;
+
+        // take a bite out of the rock
+        if self.bite_chance > 0.0 && rng.random_bool(self.bite_chance as f64) {
+            radius = self.base_radius * (1.0 - self.bite_depth);
+        }
 
         // Direct return without return keyword
         Vec2::new(angle.cos() * radius, angle.sin() * radius)
@@ -95,6 +110,137 @@ fn is_ccw(vertices: &[Vec2]) -> bool {
     final_area < 0.0 // Negative area means CCW
 }
 
+/// Split a polygon into triangles by ear clipping, works for concave outlines too
+///
+/// The polygon must be counter-clockwise and must not cross itself. Returns the
+/// triangles as indices into `vertices`, counter-clockwise as well.
+///
+/// Rust Concept: Ear clipping
+/// An "ear" is a corner that turns left with no other vertex inside its triangle.
+/// Every simple polygon has one, so cutting ears off one at a time always finishes.
+pub fn triangulate(vertices: &[Vec2]) -> Vec<[usize; 3]> {
+    let mut remaining: Vec<usize> = (0..vertices.len()).collect();
+    let mut triangles = Vec::with_capacity(vertices.len().saturating_sub(2));
+
+    while remaining.len() > 3 {
+        let count = remaining.len();
+        let corner = |i: usize| {
+            [
+                remaining[(i + count - 1) % count],
+                remaining[i],
+                remaining[(i + 1) % count],
+            ]
+        };
+
+        // a degenerate outline (say a sliver from a cut) might have no clean ear,
+        // clip the first left turn anyway rather than loop forever
+        let ear = (0..count)
+            .find(|&i| is_ear(vertices, &remaining, corner(i)))
+            .or_else(|| (0..count).find(|&i| turns_left(vertices, corner(i))))
+            .unwrap_or(0);
+
+        triangles.push(corner(ear));
+        remaining.remove(ear);
+    }
+
+    if let [a, b, c] = remaining[..] {
+        triangles.push([a, b, c]);
+    }
+    triangles
+}
+
+/// Break a polygon into a few convex pieces that cover it exactly
+///
+/// Physics engines only collide convex shapes quickly, so a concave rock becomes
+/// a compound of these. Same rules as `triangulate` for the input.
+///
+/// Rust Concept: Greedy merging (Hertel-Mehlhorn)
+/// Start from the triangles and glue neighbours back together whenever the result
+/// is still convex. Not the fewest pieces possible, but never more than 4x that.
+pub fn convex_decomposition(vertices: &[Vec2]) -> Vec<Vec<Vec2>> {
+    let mut pieces: Vec<Vec<usize>> = triangulate(vertices)
+        .into_iter()
+        .map(|triangle| triangle.to_vec())
+        .collect();
+
+    // Rust Concept: Labeled loops
+    // `continue 'merging` restarts the search after every successful merge
+    'merging: loop {
+        for i in 0..pieces.len() {
+            for j in (i + 1)..pieces.len() {
+                if let Some(merged) = merge_pieces(&pieces[i], &pieces[j])
+                    && is_convex(vertices, &merged)
+                {
+                    pieces[i] = merged;
+                    pieces.swap_remove(j);
+                    continue 'merging;
+                }
+            }
+        }
+        break;
+    }
+
+    pieces
+        .into_iter()
+        .map(|piece| piece.iter().map(|&i| vertices[i]).collect())
+        .collect()
+}
+
+// corner [prev, current, next] turns counter-clockwise
+fn turns_left(vertices: &[Vec2], [a, b, c]: [usize; 3]) -> bool {
+    (vertices[b] - vertices[a]).perp_dot(vertices[c] - vertices[b]) > 0.0
+}
+
+fn is_ear(vertices: &[Vec2], remaining: &[usize], corner: [usize; 3]) -> bool {
+    if !turns_left(vertices, corner) {
+        return false;
+    }
+
+    let [a, b, c] = corner.map(|i| vertices[i]);
+    remaining
+        .iter()
+        .filter(|i| !corner.contains(i))
+        .all(|&i| !in_triangle(vertices[i], a, b, c))
+}
+
+// inside or on the edge of a counter-clockwise triangle
+fn in_triangle(point: Vec2, a: Vec2, b: Vec2, c: Vec2) -> bool {
+    (b - a).perp_dot(point - a) >= 0.0
+        && (c - b).perp_dot(point - b) >= 0.0
+        && (a - c).perp_dot(point - c) >= 0.0
+}
+
+// Join two pieces along the edge they share, None if they aren't neighbours
+// Both wind counter-clockwise, so the shared edge runs opposite ways in each
+fn merge_pieces(first: &[usize], second: &[usize]) -> Option<Vec<usize>> {
+    for k in 0..first.len() {
+        let (from, to) = (first[k], first[(k + 1) % first.len()]);
+        let Some(m) =
+            (0..second.len()).find(|&m| second[m] == to && second[(m + 1) % second.len()] == from)
+        else {
+            continue;
+        };
+
+        // all of the first piece from `to` round to `from`, then the rest of the second
+        let mut merged: Vec<usize> = (1..=first.len())
+            .map(|step| first[(k + step) % first.len()])
+            .collect();
+        merged.extend((2..second.len()).map(|step| second[(m + step) % second.len()]));
+        return Some(merged);
+    }
+    None
+}
+
+// no corner turns right, straight corners are fine
+fn is_convex(vertices: &[Vec2], piece: &[usize]) -> bool {
+    let count = piece.len();
+    (0..count).all(|i| {
+        let [a, b, c] =
+            [piece[i], piece[(i + 1) % count], piece[(i + 2) % count]].map(|index| vertices[index]);
+        (b - a).perp_dot(c - b) >= -1e-4
+    })
+}
+
 /// Create vertices for a regular polygon (for testing/simple cases)
 ///
 /// Rust Concept: Generic functions with trait bounds
@@ -138,7 +284,7 @@ pub fn simplify_polygon(vertices: &mut Vec<Vec2>, min_distance: f32) {
 }
 
 #[cfg(test)]
-mod tests {
+pub(crate) mod tests {
     use super::*;
 
     /// Rust Concept: Unit testing
@@ -149,6 +295,47 @@ mod tests {
         assert_eq!(vertices.len(), 6);
     }
 
+    // an arrowhead pointing right, notched at (1, 0), the slicing tests use it too
+    pub(crate) fn arrowhead() -> Vec<Vec2> {
+        vec![
+            Vec2::new(0.0, -2.0),
+            Vec2::new(4.0, 0.0),
+            Vec2::new(0.0, 2.0),
+            Vec2::new(1.0, 0.0),
+        ]
+    }
+
+    #[test]
+    fn test_triangulate_concave() {
+        let vertices = arrowhead();
+        let triangles = triangulate(&vertices);
+        assert_eq!(triangles.len(), 2);
+
+        // the triangles cover the outline exactly, none of them flipped
+        let area: f32 = triangles
+            .iter()
+            .map(|t| {
+                let [a, b, c] = t.map(|i| vertices[i]);
+                (b - a).perp_dot(c - a) * 0.5
+            })
+            .sum();
+        assert!((area - crate::shapes::polygon_area(&vertices)).abs() < 1e-5);
+    }
+
+    #[test]
+    fn test_convex_decomposition() {
+        // a square keeps its single piece
+        assert_eq!(convex_decomposition(&regular_polygon(4, 1.0)).len(), 1);
+
+        // the notch can't be covered by one convex piece
+        let pieces = convex_decomposition(&arrowhead());
+        assert_eq!(pieces.len(), 2);
+        for piece in &pieces {
+            let indices: Vec<usize> = (0..piece.len()).collect();
+            assert!(is_convex(piece, &indices));
+        }
+    }
+
     #[test]
     fn test_ccw_detection() {
         // Square in CCW order
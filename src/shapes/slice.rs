@@ -0,0 +1,160 @@
+use super::geometry::{edges, polygon_area, signed_area};
+use bevy::prelude::*;
+
+/// Pieces smaller than this are slivers, not fragments
+const MIN_PIECE_AREA: f32 = 1.0;
+
+/// Cut a polygon along the line through `point` heading in `direction`
+///
+/// Returns every piece, the ones left of the line first, all with the winding of
+/// the input. A line that crosses a concave outline more than twice leaves more
+/// than one piece on a side. Returns None when the line misses the polygon or
+/// only shaves off slivers.
+///
+/// Rust Concept: Half-plane clipping
+/// Walking the edges once per side, keep the vertices on that side and add a new
+/// vertex wherever an edge crosses the line (Sutherland-Hodgman with one plane)
+pub fn slice_polygon(vertices: &[Vec2], point: Vec2, direction: Vec2) -> Option<Vec<Vec<Vec2>>> {
+    let direction = direction.try_normalize()?;
+    let big_enough = |piece: &Vec<Vec2>| piece.len() >= 3 && polygon_area(piece) > MIN_PIECE_AREA;
+    let mut left = clip_to_side(vertices, point, direction, 1.0);
+    let mut right = clip_to_side(vertices, point, direction, -1.0);
+    left.retain(big_enough);
+    right.retain(big_enough);
+
+    (!left.is_empty() && !right.is_empty()).then(|| [left, right].concat())
+}
+
+// keep the parts of the polygon on one side of the line: 1.0 for left, -1.0 for right
+fn clip_to_side(vertices: &[Vec2], point: Vec2, direction: Vec2, side: f32) -> Vec<Vec<Vec2>> {
+    let distance = |v: Vec2| direction.perp_dot(v - point) * side;
+    // start the walk from the other side, so every chain below is whole
+    let Some(start) = vertices.iter().position(|v| distance(*v) < 0.0) else {
+        return vec![vertices.to_vec()];
+    };
+    let walk: Vec<Vec2> = vertices[start..]
+        .iter()
+        .chain(&vertices[..start])
+        .copied()
+        .collect();
+
+    // Each chain runs from where the outline comes onto this side to where it leaves.
+    // Crossings are (how far along the line, chain, coming on)
+    let mut chains: Vec<Vec<Vec2>> = Vec::new();
+    let mut crossings: Vec<(f32, usize, bool)> = Vec::new();
+    for (a, b) in edges(&walk) {
+        let (da, db) = (distance(a), distance(b));
+        if da >= 0.0 {
+            chains
+                .last_mut()
+                .expect("the walk starts off this side")
+                .push(a);
+        }
+        // the edge crosses the line, add the crossing point
+        if (da >= 0.0) != (db >= 0.0) {
+            let crossing = a.lerp(b, da / (da - db));
+            let coming_on = da < 0.0;
+            if coming_on {
+                chains.push(Vec::new());
+            }
+            chains.last_mut().expect("a chain is open").push(crossing);
+            crossings.push((direction.dot(crossing - point), chains.len() - 1, coming_on));
+        }
+    }
+
+    // Rust Concept: Pairing up crossings
+    // Along the line the crossings go in and out of the polygon in turn, so sorted
+    // they pair up into the stretches of line inside it. Each stretch joins the end
+    // of one chain to the start of another, and every loop of joined chains is a
+    // piece of its own, not welded to the others by a seam along the cut
+    crossings.sort_by(|a, b| a.0.total_cmp(&b.0));
+    let mut next: Vec<Option<usize>> = vec![None; chains.len()];
+    for pair in crossings.chunks_exact(2) {
+        match (pair[0], pair[1]) {
+            ((_, leaving, false), (_, entering, true))
+            | ((_, entering, true), (_, leaving, false)) => {
+                next[leaving] = Some(entering);
+            }
+            // a corner touching the line exactly can upset the order,
+            // fall back to one outline in the order it was walked
+            _ => return vec![chains.concat()],
+        }
+    }
+
+    let mut pieces = Vec::new();
+    let mut used = vec![false; chains.len()];
+    for first in 0..chains.len() {
+        let mut piece = Vec::new();
+        let mut chain = Some(first);
+        while let Some(i) = chain.filter(|&i| !used[i]) {
+            used[i] = true;
+            piece.extend(&chains[i]);
+            chain = next[i];
+        }
+        if !piece.is_empty() {
+            pieces.push(piece);
+        }
+    }
+    pieces
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::shapes::polygon::tests::arrowhead;
+    use crate::shapes::triangulate;
+
+    fn square() -> Vec<Vec2> {
+        vec![
+            Vec2::new(-1.0, -1.0),
+            Vec2::new(1.0, -1.0),
+            Vec2::new(1.0, 1.0),
+            Vec2::new(-1.0, 1.0),
+        ]
+    }
+
+    #[test]
+    fn test_slice_keeps_all_the_area() {
+        // an off-centre diagonal cut
+        let pieces =
+            slice_polygon(&square(), Vec2::new(0.5, 0.0), Vec2::new(1.0, 2.0)).expect("it cuts");
+        let [left, right] = &pieces[..] else {
+            panic!("a square only cuts in two");
+        };
+
+        let total = polygon_area(left) + polygon_area(right);
+        assert!((total - 4.0).abs() < 1e-4);
+        assert!(polygon_area(left) > polygon_area(right));
+        // both halves still wind counter-clockwise
+        assert!(signed_area(left) > 0.0 && signed_area(right) > 0.0);
+    }
+
+    #[test]
+    fn test_slice_across_a_notch() {
+        // straight down through both barbs, just behind the notch
+        let arrowhead: Vec<Vec2> = arrowhead().iter().map(|v| *v * 4.0).collect();
+        let pieces = slice_polygon(&arrowhead, Vec2::new(2.0, 0.0), Vec2::Y).expect("it cuts");
+
+        // the two barb tips come off separately, not joined by a seam
+        assert_eq!(pieces.len(), 3);
+        assert!(pieces[..2].iter().all(|tip| tip.len() == 3));
+        let total: f32 = pieces.iter().map(|piece| polygon_area(piece)).sum();
+        assert!((total - polygon_area(&arrowhead)).abs() < 1e-3);
+
+        // each piece triangulates without anything spilling outside it
+        for piece in &pieces {
+            assert!(signed_area(piece) > 0.0);
+            let covered: f32 = triangulate(piece)
+                .iter()
+                .map(|t| signed_area(&[piece[t[0]], piece[t[1]], piece[t[2]]]))
+                .sum();
+            assert!((covered - polygon_area(piece)).abs() < 1e-3);
+        }
+    }
+
+    #[test]
+    fn test_slice_that_misses() {
+        assert!(slice_polygon(&square(), Vec2::new(5.0, 0.0), Vec2::Y).is_none());
+        assert!(slice_polygon(&square(), Vec2::ZERO, Vec2::ZERO).is_none());
+    }
+}
@@ -0,0 +1,41 @@
+//! Small key/value persistence for save data
+//!
+//! Native builds write one plain-text file per key in the working directory,
+//! the wasm32 build stores the same text in the browser's localStorage.
+
+#[cfg(target_arch = "wasm32")]
+const STORAGE_PREFIX: &str = "asteroid_dodge/";
+
+/// Read the saved contents for `key`, None if nothing has been saved yet
+#[cfg(not(target_arch = "wasm32"))]
+pub fn read(key: &str) -> Option<String> {
+    std::fs::read_to_string(key).ok()
+}
+
+/// Save `contents` under `key`, replacing anything already there
+#[cfg(not(target_arch = "wasm32"))]
+pub fn write(key: &str, contents: &str) -> Result<(), String> {
+    std::fs::write(key, contents).map_err(|e| format!("failed to write {key}: {e}"))
+}
+
+#[cfg(target_arch = "wasm32")]
+fn local_storage() -> Option<web_sys::Storage> {
+    // Rust Concept: ? on Option
+    // window() and local_storage() can both be missing (e.g. private browsing)
+    web_sys::window()?.local_storage().ok()?
+}
+
+#[cfg(target_arch = "wasm32")]
+pub fn read(key: &str) -> Option<String> {
+    local_storage()?
+        .get_item(&format!("{STORAGE_PREFIX}{key}"))
+        .ok()?
+}
+
+#[cfg(target_arch = "wasm32")]
+pub fn write(key: &str, contents: &str) -> Result<(), String> {
+    let storage = local_storage().ok_or_else(|| "localStorage is unavailable".to_string())?;
+    storage
+        .set_item(&format!("{STORAGE_PREFIX}{key}"), contents)
+        .map_err(|e| format!("failed to write {key}: {e:?}"))
+}
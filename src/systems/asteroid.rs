@@ -4,22 +4,70 @@ use crate::components::*;
 use crate::resources::*;
 use crate::shapes::*;
 use avian2d::prelude::*;
+use bevy::ecs::system::SystemParam;
 use bevy::prelude::*;
 use rand::Rng;
 
+/// Everything it takes to put a fresh field asteroid into the world
+///
+/// Rust Concept: Custom SystemParams
+/// Bundling the resources spawning needs keeps the systems that spawn rocks
+/// short, and under Bevy's limit on how many parameters a system can have
+#[derive(SystemParam)]
+pub struct AsteroidSpawner<'w, 's> {
+    commands: Commands<'w, 's>,
+    meshes: ResMut<'w, Assets<Mesh>>,
+    materials: ResMut<'w, Assets<ColorMaterial>>,
+    shapes: Res<'w, ShapeRegistry>,
+    asteroid_materials: Res<'w, MaterialRegistry>,
+    tuning: Res<'w, AsteroidTuning>,
+    pub game_rng: ResMut<'w, GameRng>,
+}
+
+impl AsteroidSpawner<'_, '_> {
+    /// Spawn one rock, picking its material from the registry when it isn't given
+    pub fn spawn(
+        &mut self,
+        position: Vec3,
+        velocity: Vec2,
+        radius: f32,
+        material: Option<AsteroidMaterial>,
+    ) {
+        // Rust Concept: Reborrowing through ResMut
+        // Deref once so we can borrow the separate streams independently
+        let game_rng = &mut *self.game_rng;
+        let material =
+            material.unwrap_or_else(|| self.asteroid_materials.pick(&mut game_rng.spawning));
+
+        spawn_asteroid_entity(
+            &mut self.commands,
+            &mut self.meshes,
+            &mut self.materials,
+            &mut game_rng.shapes,
+            &self.shapes,
+            &self.tuning,
+            position,
+            velocity,
+            radius,
+            material,
+        );
+    }
+}
+
 /// Spawn asteroids at intervals with procedurally generated shapes
+/// Once the campaign is over this is what keeps the rocks coming
 ///
 /// Rust Concept: Complex system with multiple resources
 #[allow(clippy::too_many_arguments)]
 pub fn spawn_asteroids(
-    mut commands: Commands,
+    mut spawner: AsteroidSpawner,
     mut spawn_timer: ResMut<SpawnTimer>,
     difficulty: Res<DifficultyConfig>,
     config: Res<AsteroidSpawnConfig>,
+    director: Res<Director>,
+    player: Query<(&Transform, &LinearVelocity), With<Player>>,
     time: Res<Time>,
     app_state: Res<State<AppState>>,
-    mut meshes: ResMut<Assets<Mesh>>,
-    mut materials: ResMut<Assets<ColorMaterial>>,
 ) {
     if *app_state.get() != AppState::Playing {
         return;
@@ -35,7 +83,9 @@ pub fn spawn_asteroids(
         return;
     }
 
-    let new_interval = difficulty.calculate_interval(spawn_timer.elapsed_time);
+    // the curve sets the pace, the director speeds it up or slows it down for this player
+    let new_interval =
+        difficulty.calculate_interval(spawn_timer.elapsed_time) * director.interval_scale;
 
     //reset spawn timer with new duration
     spawn_timer
@@ -43,52 +93,67 @@ pub fn spawn_asteroids(
         .set_duration(std::time::Duration::from_secs_f32(new_interval));
     spawn_timer.timer.reset();
 
-    // Rust Concept: Creating thread-local RNG
-    // This is cheaper than using a global RNG with locking
-    let mut rng = rand::rng();
-
-    // Random spawn position
-    let x = rng.random_range(-config.spawn_x_range..config.spawn_x_range);
-    let y = config.spawn_y;
-
-    // Random size
-    // Rust Concept: Match expression for weighted random choice
-    let size = match rng.random_range(0..100) {
-        0..=40 => AsteroidSize::Small,   // 40% chance
-        41..=75 => AsteroidSize::Medium, // 35% chance
-        _ => AsteroidSize::Large,        // 25% chance
+    let rng = &mut spawner.game_rng.spawning;
+
+    // Random spawn position, somewhere along one of the edges
+    let edge = config.pick_edge(rng);
+    let position = config.edge_point(edge, rng.random_range(-1.0..1.0));
+
+    // Random size, anywhere in the range but mostly on the small side
+    // Rust Concept: Shaping a uniform random number
+    // Raising a 0..1 roll to a power above 1 bunches the results toward 0
+    let roll: f32 = rng.random_range(0.0..1.0);
+    let size_bias = config.size_bias * director.size_bias_scale;
+    let radius = config.min_radius + (config.max_radius - config.min_radius) * roll.powf(size_bias);
+
+    // Random velocity, into the screen with slight sideways variation
+    let speed = rng.random_range(config.min_speed..config.max_speed);
+    let drift = rng.random_range(-50.0..50.0);
+    // every roll happens whether or not it's used, so one rock's choices can't
+    // shift the random numbers of every rock after it
+    let aimed = rng.random_bool(config.aim_chance.into());
+    let spread = rng.random_range(-1.0..=1.0) * config.aim_spread.to_radians();
+
+    // aimed rocks go for the ship, the rest come straight in
+    let target = player.single().ok().filter(|_| aimed);
+    let aim = target.and_then(|(ship, ship_velocity)| {
+        config.aim(
+            position,
+            speed,
+            ship.translation.truncate(),
+            ship_velocity.0,
+        )
+    });
+    let velocity = match aim {
+        Some(direction) => Vec2::from_angle(spread).rotate(direction) * speed,
+        None => edge.inward() * speed + edge.inward().perp() * drift,
     };
 
-    // Random velocity (downward with slight horizontal variation)
-    let speed_y = -rng.random_range(config.min_speed..config.max_speed);
-    let speed_x = rng.random_range(-50.0..50.0);
-
-    let velocity = Vec2::new(speed_x, speed_y);
-    let position = Vec3::new(x, y, 0.0);
-
-    spawn_asteroid_entity(
-        &mut commands,
-        &mut meshes,
-        &mut materials,
-        position,
-        velocity,
-        size,
+    spawner.spawn(
+        position.extend(0.0),
+        velocity * director.speed_scale,
+        radius,
+        None,
     );
 }
 
 // Helper function to spawn asteroids
+// rng decides the outline and spin, pass the stream the caller owns
+#[allow(clippy::too_many_arguments)]
 pub fn spawn_asteroid_entity(
     commands: &mut Commands,
     meshes: &mut ResMut<Assets<Mesh>>,
     materials: &mut ResMut<Assets<ColorMaterial>>,
+    rng: &mut impl Rng,
+    shapes: &ShapeRegistry,
+    tuning: &AsteroidTuning,
     position: Vec3,
     velocity: Vec2,
-    size: AsteroidSize,
+    radius: f32,
+    material: AsteroidMaterial,
 ) {
-    let mut rng = rand::rng();
-    // 1. Generate Asteroid shape
-    let generator = IrregularPolygonGenerator::new(size.vertex_count(), size.radius());
-    let mut vertices = generator.generate(&mut rng);
+    // 1. Generate Asteroid shape, picking what kind of rock it is first
+    let mut vertices = shapes.pick(rng).generate(radius, rng);
 
     // Ensure vertices are in the correct order for physics
     ensure_ccw(&mut vertices);
@@ -96,22 +161,61 @@ pub fn spawn_asteroid_entity(
     // Simplify to avoid tiny edges that can cause physics issues
     simplify_polygon(&mut vertices, 5.0);
 
-    // Rust Concept: Clone for both visual and physics
-    // We need separate copies because the systems might modify them
-    let visual_vertices = vertices.clone();
+    spawn_asteroid(
+        commands,
+        meshes,
+        materials,
+        tuning,
+        AsteroidSpawn {
+            vertices,
+            transform: Transform::from_translation(position),
+            velocity,
+            angular_velocity: rng.random_range(-2.0..2.0),
+            material,
+        },
+    );
+}
+
+/// Everything needed to put one asteroid in the world
+/// Fresh rocks are generated, fragments are cut from their parent
+pub struct AsteroidSpawn {
+    pub vertices: Vec<Vec2>, // outline around the transform, counter-clockwise
+    pub transform: Transform,
+    pub velocity: Vec2,
+    pub angular_velocity: f32,
+    pub material: AsteroidMaterial,
+}
+
+/// Spawn an asteroid with exactly the given outline
+/// Its size, mass and spin resistance all come from that outline and its material
+pub fn spawn_asteroid(
+    commands: &mut Commands,
+    meshes: &mut ResMut<Assets<Mesh>>,
+    materials: &mut ResMut<Assets<ColorMaterial>>,
+    tuning: &AsteroidTuning,
+    spawn: AsteroidSpawn,
+) {
+    // Centre the outline on its centroid so the transform sits at the centre of mass
+    let centre = polygon_centroid(&spawn.vertices);
+    let vertices: Vec<Vec2> = spawn.vertices.iter().map(|v| *v - centre).collect();
+    let mut transform = spawn.transform;
+    transform.translation += transform.rotation * centre.extend(0.0);
+
+    let size = AsteroidSize::from_outline(&vertices);
+    let material = spawn.material;
+    let mass = size.mass(tuning) * material.density();
+    let hit_points = size.hit_points(tuning) * material.density() * material.toughness();
+    let inertia = polygon_moment_of_inertia(&vertices, mass);
 
-    // Create physics collider from vertices
-    // Rust Concept: Error handling with expect
-    // This converts Result to a panic with a custom message if it fails
-    let collider =
-        Collider::convex_hull(vertices).expect("Failed to create convex hull for asteroid");
+    // Create physics collider matching the outline, dents and all
+    let collider = polygon_collider(&vertices);
 
     // Create visual mesh from polygon entities
-    let mesh = create_polygon_mesh(&visual_vertices);
+    let mesh = create_polygon_mesh(&vertices);
     let mesh_handle = meshes.add(mesh);
 
     // create material with asteroid color
-    let material = materials.add(ColorMaterial::from(Color::srgb(0.5, 0.5, 0.7)));
+    let color_material = materials.add(ColorMaterial::from(material.color()));
 
     // Spawn the asteroid entity
     // Rust Concept: Long tuple of components
@@ -119,35 +223,45 @@ pub fn spawn_asteroid_entity(
     commands.spawn((
         // Visual (we'll render custom mesh later)
         Mesh2d(mesh_handle),
-        MeshMaterial2d(material),
-        Transform::from_translation(position),
+        // every rock gets its own material so it can darken and flash on its own
+        MeshMaterial2d(color_material),
+        transform,
         // Game components
         Asteroid,
         size,
+        material,
+        Health::new(hit_points),
+        // no armour, but shots and blasts still go through apply_damage like on the ship
+        Resistances::default(),
+        // kept so the rock can be cut apart later
+        AsteroidShape(vertices),
         Cleanup,
         // Physics components
         RigidBody::Dynamic,
         collider,
         CollisionEventsEnabled,
-        LinearVelocity(velocity),
-        AngularVelocity(rng.random_range(-2.0..2.0)),
-        Mass(size.mass()),
+        LinearVelocity(spawn.velocity),
+        AngularVelocity(spawn.angular_velocity),
+        Mass(mass),
+        AngularInertia(inertia),
         // Restitution (bounciness) - asteroids bounce off each other a bit
-        Restitution::new(0.8),
+        Restitution::new(material.restitution()),
+        DespawnOnExit(AppState::Playing),
     ));
 }
 
-/// Clean up asteroids that have left the screen
+/// Clean up asteroids and power-ups that have left the screen, off any side
 ///
 /// Rust Concept: Filtering entities with With<> query filter
-#[allow(clippy::type_complexity)]
 pub fn cleanup_offscreen(
     mut commands: Commands,
-    query: Query<(Entity, &Transform), (With<Asteroid>, With<Cleanup>)>,
+    config: Res<AsteroidSpawnConfig>,
+    query: Query<(Entity, &Transform, Option<&LinearVelocity>), With<Cleanup>>,
 ) {
-    for (entity, transform) in &query {
-        // Despawn if below the screen
-        if transform.translation.y < -350.0 {
+    for (entity, transform, velocity) in &query {
+        // Despawn once it's past the edge and heading away, rocks bounced back up included
+        let velocity = velocity.map_or(Vec2::ZERO, |velocity| velocity.0);
+        if config.is_gone(transform.translation.truncate(), velocity) {
             // Rust Concept: Entity despawning
             // This marks the entity for removal
             commands.entity(entity).despawn();
@@ -155,6 +269,105 @@ pub fn cleanup_offscreen(
     }
 }
 
+/// Scratch a crack into an asteroid where a shot hit it without breaking it
+///
+/// The crack is a thin dark child mesh running from the impact point toward the
+/// centre, longer for harder hits, so it turns and moves with the rock
+#[allow(clippy::too_many_arguments)]
+pub fn crack_asteroid(
+    commands: &mut Commands,
+    meshes: &mut ResMut<Assets<Mesh>>,
+    materials: &mut ResMut<Assets<ColorMaterial>>,
+    asteroid: Entity,
+    asteroid_transform: &Transform,
+    size: AsteroidSize,
+    impact_point: Vec3,
+    damage_fraction: f32,
+) {
+    // Rust Concept: World to local space
+    // Undo the asteroid's translation and rotation to find the hit on the rock itself
+    let local = (asteroid_transform.rotation.inverse()
+        * (impact_point - asteroid_transform.translation))
+        .truncate()
+        .clamp_length_max(size.radius());
+    let inward = -local.normalize_or(Vec2::Y);
+    let length = size.radius() * (0.3 + damage_fraction).min(1.0);
+
+    let crack = commands
+        .spawn((
+            Mesh2d(meshes.add(Rectangle::new(2.0, length))),
+            MeshMaterial2d(materials.add(ColorMaterial::from(Color::srgb(0.15, 0.15, 0.2)))),
+            Transform::from_translation((local + inward * length * 0.5).extend(0.1))
+                .with_rotation(Quat::from_rotation_arc_2d(Vec2::Y, inward)),
+        ))
+        .id();
+    commands
+        .entity(asteroid)
+        .add_child(crack)
+        .insert(HitFlash::default());
+}
+
+/// Darken asteroids as they weaken and flash them white for a moment when hit
+#[allow(clippy::type_complexity)]
+pub fn update_asteroid_damage(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut query: Query<
+        (
+            Entity,
+            &Health,
+            &AsteroidMaterial,
+            &MeshMaterial2d<ColorMaterial>,
+            Option<&mut HitFlash>,
+        ),
+        (With<Asteroid>, Or<(Changed<Health>, With<HitFlash>)>),
+    >,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+) {
+    for (entity, health, asteroid_material, material, flash) in query.iter_mut() {
+        // full health is the material's own color, a nearly broken rock is much darker
+        let shade = 0.4 + 0.6 * health.fraction();
+        let base = asteroid_material.color().to_srgba();
+        let mut color = Color::srgb(base.red * shade, base.green * shade, base.blue * shade);
+
+        if let Some(mut flash) = flash {
+            flash.timer.tick(time.delta());
+            if flash.timer.is_finished() {
+                commands.entity(entity).remove::<HitFlash>();
+            } else {
+                color = Color::WHITE;
+            }
+        }
+
+        if let Some(material) = materials.get_mut(&material.0) {
+            material.color = color;
+        }
+    }
+}
+
+/// A collider that follows the outline exactly, concave parts included
+///
+/// Rust Concept: Compound colliders
+/// Avian collides convex shapes, so a concave rock is one convex hull per piece
+/// of its decomposition, all attached to the same body
+fn polygon_collider(vertices: &[Vec2]) -> Collider {
+    let mut parts: Vec<(Position, Rotation, Collider)> = convex_decomposition(vertices)
+        .into_iter()
+        .filter_map(Collider::convex_hull)
+        .map(|hull| (Position::default(), Rotation::default(), hull))
+        .collect();
+
+    match parts.len() {
+        // every piece was a sliver, the hull is close enough
+        // Rust Concept: Error handling with expect
+        // This converts an Option to a panic with a custom message if it's None
+        0 => Collider::convex_hull(vertices.to_vec())
+            .expect("Failed to create convex hull for asteroid"),
+        1 => parts.remove(0).2,
+        _ => Collider::compound(parts),
+    }
+}
+
 // Creating a polygon mesh
 fn create_polygon_mesh(vertices: &[Vec2]) -> Mesh {
     use bevy::asset::RenderAssetUsages;
@@ -163,13 +376,12 @@ fn create_polygon_mesh(vertices: &[Vec2]) -> Mesh {
     // we need at least 3 vertices for a polygon
     assert!(vertices.len() >= 3, "Polygon must have at least 3 vertices");
 
-    // Use fan triangulation
-    let mut indices = Vec::new();
-    for i in 1..(vertices.len() - 1) {
-        indices.push(0u32);
-        indices.push(i as u32);
-        indices.push((i + 1) as u32);
-    }
+    // Ear clipping, so concave outlines draw correctly (a fan would spill over the dents)
+    let indices: Vec<u32> = triangulate(vertices)
+        .into_iter()
+        .flatten()
+        .map(|i| i as u32)
+        .collect();
 
     //convert vec2 to vec3 by adding z=0
     // iterator map for transformation
@@ -1,6 +1,6 @@
-use crate::components::{Camera, DamageSource};
+use crate::components::{Camera, DamageKind};
 use crate::events::DamageEvent;
-use crate::resources::{CameraTarget, ScreenShake};
+use crate::resources::{CameraTarget, GameRng, ScreenShake};
 use bevy::prelude::*;
 use rand::Rng;
 
@@ -16,9 +16,12 @@ pub fn trigger_screen_shake(
     mut shaker: ResMut<ScreenShake>,
 ) {
     for event in events.read() {
+        // as hard as the hit was, grinding against a rock only rumbles
+        let damage = event.damage();
         shaker.trauma += ({
-            match event.source_type {
-                DamageSource::AsteroidEntity(size) => size.damage() / 60.0_f32,
+            match event.source_type.kind() {
+                DamageKind::Explosion => damage / 40.0, // explosions shake harder
+                _ => damage / 60.0_f32,
             }
         })
         .min(1.0);
@@ -26,11 +29,15 @@ pub fn trigger_screen_shake(
     }
 }
 
-pub fn update_screen_shake(mut shaker: ResMut<ScreenShake>, time: Res<Time>) {
+pub fn update_screen_shake(
+    mut shaker: ResMut<ScreenShake>,
+    mut game_rng: ResMut<GameRng>,
+    time: Res<Time>,
+) {
     //if active: calculate random offset based on intensity
     if shaker.trauma > 0.001 {
         let magnitude = shaker.trauma * shaker.trauma * shaker.max_offset;
-        let mut rng = rand::rng();
+        let rng = &mut game_rng.cosmetic;
         shaker.offset = Vec3::new(
             rng.random_range(-magnitude..magnitude),
             rng.random_range(-magnitude..magnitude),
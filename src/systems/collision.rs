@@ -1,18 +1,31 @@
 use crate::components::*;
-use crate::events::{DamageEvent, PlaySoundEvent};
+use crate::events::{
+    AsteroidDestroyed, BlastEvent, DamageEvent, HealthChanged, PlaySoundEvent, ProjectileHit,
+};
 use crate::resources::*;
-use crate::systems::asteroid::spawn_asteroid_entity;
+use crate::shapes::{polygon_area, polygon_centroid, slice_polygon};
+use crate::systems::asteroid::{AsteroidSpawn, crack_asteroid, spawn_asteroid};
+use crate::systems::health::apply_damage;
+use crate::systems::power_up::spawn_score_gem;
 use avian2d::prelude::*;
+use bevy::ecs::system::SystemParam;
 use bevy::prelude::*;
 use bevy_hanabi::prelude::*;
+use rand::Rng;
+use std::f32::consts::PI;
 
 /// Handle collisions between player and asteroids (simplified version)
 ///
+/// The damage depends on how hard the two actually hit: a slow rock nudging the
+/// ship barely scratches it, ramming a big one head-on is a disaster.
+///
 /// Rust Concept: Breaking complex logic into helper functions
 pub fn handle_collisions_simple(
     mut collision_events: MessageReader<CollisionStart>,
-    player_query: Query<(Entity, &Transform), With<Player>>,
-    asteroid_query: Query<(Entity, &AsteroidSize), With<Asteroid>>,
+    collisions: Collisions,
+    player_query: Query<(Entity, &Transform, &Mass), With<Player>>,
+    asteroid_query: Query<(&Transform, &Mass), With<Asteroid>>,
+    tuning: Res<AsteroidTuning>,
     mut message: MessageWriter<DamageEvent>,
 ) {
     for event in collision_events.read() {
@@ -23,7 +36,17 @@ pub fn handle_collisions_simple(
         if let Some(collision) =
             check_player_asteroid_collision(entity1, entity2, &player_query, &asteroid_query)
         {
-            handle_collision(collision, &mut message );
+            // the solver has already pushed them apart, so read back how hard it had to push
+            let Some(contacts) = collisions.get(event.collider1, event.collider2) else {
+                continue;
+            };
+            handle_collision(
+                collision,
+                total_normal_impulse(contacts),
+                combined_restitution(contacts),
+                &tuning,
+                &mut message,
+            );
         }
 
         // Check Projectile-Asteroid
@@ -35,145 +58,466 @@ pub fn handle_collisions_simple(
     }
 }
 
-/// Handle collisions between projectiles and asteroids
-#[allow(clippy::too_many_arguments)]
-pub fn handle_projectile_collisions(
-    mut commands: Commands,
-    mut collision_events: MessageReader<CollisionStart>,
-    mut game_state: ResMut<GameData>,
-    projectile_query: Query<(Entity, &LinearVelocity, &Transform), With<Projectile>>,
-    asteroid_query: Query<(Entity, &AsteroidSize, &Transform, &LinearVelocity), With<Asteroid>>,
-    mut effects: ResMut<Assets<EffectAsset>>,
-    mut meshes: ResMut<Assets<Mesh>>,
-    mut materials: ResMut<Assets<ColorMaterial>>,
-    mut message: MessageWriter<PlaySoundEvent>,
+/// Grind the hull down while the ship stays pressed against a rock
+///
+/// CollisionStart only comes once per contact, so a ship pinned against an
+/// asteroid would otherwise take the first hit and then nothing at all.
+pub fn handle_sustained_contact(
+    player_query: Query<(Entity, &Transform, &CollidingEntities), With<Player>>,
+    asteroid_query: Query<&Transform, With<Asteroid>>,
+    config: Res<DamageConfig>,
+    time: Res<Time>,
+    mut message: MessageWriter<DamageEvent>,
 ) {
-    for event in collision_events.read() {
-        let entity1 = event.body1.unwrap();
-        let entity2 = event.body2.unwrap();
+    let Ok((player, transform, colliding)) = player_query.single() else {
+        return;
+    };
+    let Some(rock) = colliding
+        .iter()
+        .find_map(|entity| asteroid_query.get(*entity).ok())
+    else {
+        return;
+    };
+    let damage = config.contact_damage_per_second * time.delta_secs();
+    if damage <= 0.0 {
+        return;
+    }
 
-        let projectile_entity;
-        let projectile_velocity;
-        let projectile_position;
-        let asteroid_entity;
-        let asteroid_size;
-        let asteroid_position;
-        let asteroid_velocity;
-
-        if let Ok((proj_ent, proj_vel, proj_trans)) = projectile_query.get(entity1) {
-            projectile_entity = proj_ent;
-            projectile_velocity = proj_vel.0;
-            projectile_position = proj_trans.translation;
-            if let Ok((ast_ent, size, transform, ast_vel)) = asteroid_query.get(entity2) {
-                asteroid_entity = ast_ent;
-                asteroid_size = size;
-                asteroid_position = transform.translation;
-                asteroid_velocity = ast_vel.0;
-            } else {
-                continue;
+    message.write(DamageEvent {
+        player,
+        position: transform.translation,
+        from: rock.translation,
+        source_type: DamageSource::Contact(damage),
+    });
+}
+
+/// How much the contact had to push two bodies apart, over all its points
+fn total_normal_impulse(contacts: &ContactPair) -> f32 {
+    contacts
+        .manifolds
+        .iter()
+        .flat_map(|manifold| &manifold.points)
+        .map(|point| point.normal_impulse)
+        .sum()
+}
+
+/// How bouncy the solver made the contact, from both bodies' restitution
+fn combined_restitution(contacts: &ContactPair) -> f32 {
+    contacts
+        .manifolds
+        .iter()
+        .map(|manifold| manifold.restitution)
+        .fold(0.0, f32::max)
+}
+
+/// How fast the two halves of a fractured asteroid move apart
+const SPLIT_SPEED: f32 = 100.0;
+/// A shot's mass for every point of damage it does, for knocking rocks about
+const SHOT_MASS_PER_DAMAGE: f32 = 0.05;
+/// The most one shot can change a rock's velocity by, so tiny rocks don't fly off
+const MAX_KICK: f32 = 150.0;
+/// Pieces smaller than a circle this size crumble to dust instead of flying off
+const MIN_FRAGMENT_RADIUS: f32 = 8.0;
+
+/// A rock whose health just ran out, and the line it comes apart along
+pub struct BrokenAsteroid<'a> {
+    pub entity: Entity,
+    pub size: AsteroidSize,
+    pub material: AsteroidMaterial,
+    pub shape: &'a [Vec2],
+    pub transform: &'a Transform,
+    pub velocity: Vec2, // including whatever knock broke it
+    pub spin: f32,
+    pub cut_point: Vec3,
+    pub cut_dir: Vec2,
+}
+
+/// Everything it takes to crack an asteroid or break it up
+///
+/// Shots and blasts both end up here, so a rock comes apart the same way
+/// whatever killed it
+#[derive(SystemParam)]
+pub struct AsteroidBreaker<'w, 's> {
+    commands: Commands<'w, 's>,
+    game_state: ResMut<'w, GameData>,
+    effects: ResMut<'w, Assets<EffectAsset>>,
+    meshes: ResMut<'w, Assets<Mesh>>,
+    materials: ResMut<'w, Assets<ColorMaterial>>,
+    sounds: MessageWriter<'w, PlaySoundEvent>,
+    destroyed: MessageWriter<'w, AsteroidDestroyed>,
+    power_up_config: Res<'w, PowerUpConfig>,
+    tuning: Res<'w, AsteroidTuning>,
+    game_rng: ResMut<'w, GameRng>,
+}
+
+impl AsteroidBreaker<'_, '_> {
+    /// Crack a rock that took damage but is still in one piece
+    pub fn crack(
+        &mut self,
+        entity: Entity,
+        transform: &Transform,
+        size: AsteroidSize,
+        impact_point: Vec3,
+        damage_fraction: f32,
+    ) {
+        crack_asteroid(
+            &mut self.commands,
+            &mut self.meshes,
+            &mut self.materials,
+            entity,
+            transform,
+            size,
+            impact_point,
+            damage_fraction,
+        );
+    }
+
+    /// Blow up a rock and break it into whatever its material leaves behind
+    ///
+    /// An explosive rock hands back its blast, the caller decides when it goes off
+    pub fn destroy(&mut self, rock: BrokenAsteroid) -> Option<BlastEvent> {
+        let position = rock.transform.translation;
+
+        self.sounds.write(PlaySoundEvent::Explosion);
+        self.commands.entity(rock.entity).despawn();
+        self.destroyed.write(AsteroidDestroyed {
+            position,
+            size: rock.size,
+        });
+
+        // Add score based on asteroid size
+        self.game_state.score += rock.size.score(&self.tuning);
+
+        // Spawn explosion particle effect
+        spawn_explosion(&mut self.commands, &mut self.effects, position);
+
+        // Rust Concept: Matching on what a rock is made of
+        match rock.material {
+            // blown to bits, nothing left to split
+            AsteroidMaterial::Explosive => {
+                return Some(BlastEvent::from_asteroid(position, rock.size));
             }
-        } else if let Ok((proj_ent, proj_vel, proj_trans)) = projectile_query.get(entity2) {
-            projectile_entity = proj_ent;
-            projectile_velocity = proj_vel.0;
-            projectile_position = proj_trans.translation;
-            if let Ok((ast_ent, size, transform, ast_vel)) = asteroid_query.get(entity1) {
-                asteroid_entity = ast_ent;
-                asteroid_size = size;
-                asteroid_position = transform.translation;
-                asteroid_velocity = ast_vel.0;
-            } else {
+            AsteroidMaterial::Crystal => spawn_score_gem(
+                &mut self.commands,
+                &mut self.meshes,
+                &mut self.materials,
+                &self.power_up_config,
+                position,
+            ),
+            AsteroidMaterial::Rock | AsteroidMaterial::Ice | AsteroidMaterial::Iron => {}
+        }
+
+        // Small rocks just crumble
+        if rock.size.can_split(&self.tuning) {
+            self.fracture(&rock);
+        }
+        None
+    }
+
+    /// Cut the rock along the line that broke it and send the pieces flying
+    fn fracture(&mut self, rock: &BrokenAsteroid) {
+        let position = rock.transform.translation;
+        let rotation = rock.transform.rotation;
+
+        // Rust Concept: World to local space
+        // The outline is stored relative to the rock, so bring the cut line into its frame
+        let to_local = rotation.inverse();
+        let local_point = (to_local * (rock.cut_point - position)).truncate();
+        let local_dir = (to_local * rock.cut_dir.extend(0.0)).truncate();
+
+        // a shot that only grazed the outline cuts through the middle instead
+        let Some(mut pieces) = slice_polygon(rock.shape, local_point, local_dir)
+            .or_else(|| slice_polygon(rock.shape, polygon_centroid(rock.shape), local_dir))
+        else {
+            return;
+        };
+
+        // Ice shatters: every extra cut splits each piece again through its middle
+        for _ in 1..rock.material.fracture_cuts() {
+            let cut_dir = Vec2::from_angle(self.game_rng.fragments.random_range(0.0..PI));
+            pieces = pieces
+                .into_iter()
+                .flat_map(
+                    |piece| match slice_polygon(&piece, polygon_centroid(&piece), cut_dir) {
+                        Some(cut) => cut,
+                        None => vec![piece],
+                    },
+                )
+                .collect();
+        }
+
+        let parent_centre = polygon_centroid(rock.shape);
+        let areas: Vec<f32> = pieces.iter().map(|piece| polygon_area(piece)).collect();
+        let centres: Vec<Vec2> = pieces.iter().map(|piece| polygon_centroid(piece)).collect();
+        let total_area: f32 = areas.iter().sum();
+
+        // Rust Concept: Conservation of momentum
+        // Each piece keeps the parent's velocity at its own centre (spin included) and
+        // is pushed away from the middle, lighter pieces harder. Taking the average push
+        // back off leaves the total momentum what the rock had plus the shot's kick
+        let pushes: Vec<Vec2> = centres
+            .iter()
+            .zip(&areas)
+            .map(|(centre, area)| {
+                (*centre - parent_centre).normalize_or_zero()
+                    * SPLIT_SPEED
+                    * (1.0 - area / total_area)
+            })
+            .collect();
+        let average_push = pushes
+            .iter()
+            .zip(&areas)
+            .map(|(push, area)| *push * *area)
+            .sum::<Vec2>()
+            / total_area;
+
+        info!(
+            "Fracturing asteroid: CutDir={:?}, Areas={:?}",
+            rock.cut_dir, areas
+        );
+
+        for (i, piece) in pieces.into_iter().enumerate() {
+            if (areas[i] / PI).sqrt() < MIN_FRAGMENT_RADIUS {
                 continue;
             }
-        } else {
+
+            let centre = centres[i];
+            let offset = (rotation * (centre - parent_centre).extend(0.0)).truncate();
+            let push = (rotation * (pushes[i] - average_push).extend(0.0)).truncate();
+            let velocity = rock.velocity + rock.spin * offset.perp() + push;
+            // pieces tumble a little differently from each other
+            let angular_velocity = rock.spin + self.game_rng.fragments.random_range(-0.5..0.5);
+
+            spawn_asteroid(
+                &mut self.commands,
+                &mut self.meshes,
+                &mut self.materials,
+                &self.tuning,
+                AsteroidSpawn {
+                    vertices: piece.iter().map(|v| *v - centre).collect(),
+                    transform: rock
+                        .transform
+                        .with_translation(position + rotation * centre.extend(0.0)),
+                    velocity,
+                    angular_velocity,
+                    // pieces are made of the same stuff as the rock they came from
+                    material: rock.material,
+                },
+            );
+        }
+    }
+}
+
+/// Handle projectiles hitting asteroids, as found by sweep_projectiles
+#[allow(clippy::type_complexity)]
+pub fn handle_projectile_collisions(
+    mut commands: Commands,
+    mut projectile_hits: MessageReader<ProjectileHit>,
+    projectile_query: Query<&Projectile>,
+    mut asteroid_query: Query<
+        (
+            &AsteroidSize,
+            &AsteroidShape,
+            &Transform,
+            &mut LinearVelocity,
+            &AngularVelocity,
+            &Mass,
+            &AsteroidMaterial,
+            &mut Health,
+            &Resistances,
+        ),
+        With<Asteroid>,
+    >,
+    mut breaker: AsteroidBreaker,
+    mut blasts: MessageWriter<BlastEvent>,
+    mut health_message: MessageWriter<HealthChanged>,
+) {
+    for hit in projectile_hits.read() {
+        let (projectile_entity, asteroid_entity) = (hit.projectile, hit.asteroid);
+        let Ok(projectile) = projectile_query.get(projectile_entity) else {
+            continue;
+        };
+        let Ok((
+            &asteroid_size,
+            shape,
+            asteroid_transform,
+            mut asteroid_velocity,
+            asteroid_spin,
+            asteroid_mass,
+            &asteroid_material,
+            mut asteroid_health,
+            resistances,
+        )) = asteroid_query.get_mut(asteroid_entity)
+        else {
+            continue;
+        };
+        let projectile_position = hit.point.extend(0.0);
+
+        // Already broken by an earlier hit this tick, its despawn just hasn't applied yet
+        if asteroid_health.is_dead() {
             continue;
         }
 
         // Collision confirmed
-        message.write(PlaySoundEvent::Explosion);
-        commands.entity(projectile_entity).despawn();
-        commands.entity(asteroid_entity).despawn();
+        // a piercing shot carries on through
+        if !projectile.piercing {
+            commands.entity(projectile_entity).despawn();
+        }
 
-        // Add score based on asteroid size
-        let score_value = match asteroid_size {
-            AsteroidSize::Small => 100,
-            AsteroidSize::Medium => 50,
-            AsteroidSize::Large => 20,
-        };
-        game_state.score += score_value;
+        // Rust Concept: Momentum
+        // The shot hands its momentum to the rock: heavy shots shove harder, heavy rocks budge less.
+        // What matters is how fast the shot was going relative to the rock
+        let relative_velocity = hit.velocity - asteroid_velocity.0;
+        let impact_dir = relative_velocity.normalize_or(hit.velocity.normalize_or_zero());
+        let kick = (relative_velocity * projectile.damage * SHOT_MASS_PER_DAMAGE
+            / asteroid_mass.0.max(f32::EPSILON))
+        .clamp_length_max(MAX_KICK);
 
-        // Spawn explosion particle effect
-        spawn_explosion(&mut commands, &mut effects, asteroid_position);
+        // Rust Concept: Damage over several hits
+        // Weak shots chip away at a rock, it only breaks once its health is gone
+        // (and heavier rocks have more of it)
+        let dealt = apply_damage(
+            asteroid_entity,
+            &mut asteroid_health,
+            resistances,
+            DamageSource::Impact(projectile.damage),
+            projectile.damage,
+            projectile_position,
+            // a step back along the shot's path
+            projectile_position - impact_dir.extend(0.0),
+            &mut health_message,
+        );
+        if !asteroid_health.is_dead() {
+            asteroid_velocity.0 += kick;
+            breaker.crack(
+                asteroid_entity,
+                asteroid_transform,
+                asteroid_size,
+                projectile_position,
+                dealt / asteroid_health.max(),
+            );
+            continue;
+        }
 
-        // 2. Determine children
-        let children_sizes = match asteroid_size {
-            AsteroidSize::Large => Some((AsteroidSize::Medium, AsteroidSize::Small)),
-            AsteroidSize::Medium => Some((AsteroidSize::Small, AsteroidSize::Small)),
-            AsteroidSize::Small => None,
-        };
-        if let Some((size1, size2)) = children_sizes {
-            // 3. Calculate Velocities
-            // Impact influence (projectile pushes asteroid)
-            let impact_impulse = projectile_velocity * 0.4; // 40% of projectile speed transfers
-            let base_velocity = asteroid_velocity + impact_impulse;
-
-            // Split force (perpendicular to PROJECTILE direction)
-            // Robust calculation: Use velocity if significant, otherwise use relative position
-            let impact_dir = if projectile_velocity.length_squared() > 1.0 {
-                projectile_velocity.normalize()
-            } else {
-                (asteroid_position - projectile_position)
-                    .truncate()
-                    .normalize_or_zero()
-            };
+        // the rock splits along the shot's path
+        let blast = breaker.destroy(BrokenAsteroid {
+            entity: asteroid_entity,
+            size: asteroid_size,
+            material: asteroid_material,
+            shape: &shape.0,
+            transform: asteroid_transform,
+            velocity: asteroid_velocity.0 + kick,
+            spin: asteroid_spin.0,
+            cut_point: projectile_position,
+            cut_dir: impact_dir,
+        });
+        if let Some(blast) = blast {
+            blasts.write(blast);
+        }
+    }
+}
 
-            // If projectile is moving (vx, vy), perpendicular is (-vy, vx)
-            let split_dir = Vec2::new(-impact_dir.y, impact_dir.x);
-            let split_speed = 100.0; // Adjust as needed
+/// Explosive asteroids going off: every rock and the ship in range takes damage
+///
+/// Rust Concept: A work queue
+/// An explosive caught in a blast goes off too, its blast joins the same list so
+/// one loop handles the whole chain reaction
+#[allow(clippy::type_complexity)]
+pub fn handle_blasts(
+    mut blast_events: MessageReader<BlastEvent>,
+    mut asteroid_query: Query<
+        (
+            Entity,
+            &Transform,
+            &AsteroidSize,
+            &AsteroidShape,
+            &LinearVelocity,
+            &AngularVelocity,
+            &AsteroidMaterial,
+            &mut Health,
+            &Resistances,
+        ),
+        With<Asteroid>,
+    >,
+    player_query: Query<(Entity, &Transform), With<Player>>,
+    mut breaker: AsteroidBreaker,
+    mut damage_message: MessageWriter<DamageEvent>,
+    mut health_message: MessageWriter<HealthChanged>,
+) {
+    let mut pending: Vec<BlastEvent> = blast_events.read().copied().collect();
 
-            info!(
-                "Splitting asteroid: ProjVel={:?}, ImpactDir={:?}, SplitDir={:?}",
-                projectile_velocity, impact_dir, split_dir
-            );
+    while let Some(blast) = pending.pop() {
+        for (entity, transform, &size, shape, velocity, spin, &material, mut health, resistances) in
+            asteroid_query.iter_mut()
+        {
+            // already gone, including the rock that set this blast off
+            if health.is_dead() {
+                continue;
+            }
+            let Some(damage) = blast.damage_at(transform.translation, size.radius()) else {
+                continue;
+            };
 
-            // One piece goes "up" (relative to impact), one goes "down"
-            let vel1 = base_velocity + (split_dir * split_speed);
-            let vel2 = base_velocity - (split_dir * split_speed);
-
-            // Calculate offset to prevent overlap
-            // We want them to start roughly edge-to-edge
-            // Distance from center = radius
-            // So we move them apart by their respective radii
-            let offset_dist = size1.radius() + size2.radius() + 5.0; // +5.0 padding
-            let offset = split_dir * (offset_dist * 0.5); // Move each half the distance
-
-            // 4. Spawn Children
-            // Note: You'll need to pass meshes/materials resources to this system
-            spawn_asteroid_entity(
-                &mut commands,
-                &mut meshes,
-                &mut materials,
-                asteroid_position + offset.extend(0.0),
-                vel1,
-                size1,
-            );
-            spawn_asteroid_entity(
-                &mut commands,
-                &mut meshes,
-                &mut materials,
-                asteroid_position - offset.extend(0.0),
-                vel2,
-                size2,
+            let dealt = apply_damage(
+                entity,
+                &mut health,
+                resistances,
+                blast.source(),
+                damage,
+                transform.translation,
+                blast.position,
+                &mut health_message,
             );
+            if !health.is_dead() {
+                // cracked from the side facing the blast
+                breaker.crack(
+                    entity,
+                    transform,
+                    size,
+                    blast.position,
+                    dealt / health.max(),
+                );
+                continue;
+            }
+
+            // torn apart along the line out from the blast
+            let outwards = (transform.translation - blast.position).truncate();
+            let chained = breaker.destroy(BrokenAsteroid {
+                entity,
+                size,
+                material,
+                shape: &shape.0,
+                transform,
+                velocity: velocity.0,
+                spin: spin.0,
+                cut_point: transform.translation,
+                cut_dir: outwards.normalize_or(Vec2::X),
+            });
+            pending.extend(chained);
+        }
+
+        // the falloff is worked out again where the ship was hit
+        if let Ok((player, transform)) = player_query.single()
+            && blast.damage_at(transform.translation, 0.0).is_some()
+        {
+            damage_message.write(DamageEvent {
+                player,
+                position: transform.translation,
+                from: blast.position,
+                source_type: blast.source(),
+            });
         }
     }
 }
 
 /// Spawn an explosion particle effect at the given position
+///
+/// The effect belongs to the current run, returns the entity so callers can re-scope it
 pub fn spawn_explosion(
     commands: &mut Commands,
     effects: &mut ResMut<Assets<EffectAsset>>,
     position: Vec3,
-) {
+) -> Entity {
     use bevy_hanabi::prelude::*;
 
     // Create color gradient for explosion
@@ -222,13 +566,16 @@ pub fn spawn_explosion(
     let effect_handle = effects.add(effect);
 
     // Spawn the effect entity - will spawn particles for 0.3s then despawn
-    commands.spawn((
-        Name::new("Explosion"),
-        ParticleEffect::new(effect_handle),
-        Transform::from_translation(position),
-        // Despawn after short time
-        Lifetime::new(0.3),
-    ));
+    commands
+        .spawn((
+            Name::new("Explosion"),
+            ParticleEffect::new(effect_handle),
+            Transform::from_translation(position),
+            // Despawn after short time
+            Lifetime::new(0.3),
+            DespawnOnExit(AppState::Playing),
+        ))
+        .id()
 }
 
 /// Helper struct to represent a player-asteroid collision
@@ -237,7 +584,9 @@ pub fn spawn_explosion(
 struct PlayerAsteroidCollision {
     player_entity: Entity,
     position: Vec3,
-    asteroid_size: AsteroidSize,
+    asteroid_position: Vec3,
+    player_mass: f32,
+    asteroid_mass: f32,
 }
 
 /// Check if two entities represent a player-asteroid collision
@@ -246,26 +595,30 @@ struct PlayerAsteroidCollision {
 fn check_player_asteroid_collision(
     entity1: Entity,
     entity2: Entity,
-    player_query: &Query<(Entity, &Transform), With<Player>>,
-    asteroid_query: &Query<(Entity, &AsteroidSize), With<Asteroid>>,
+    player_query: &Query<(Entity, &Transform, &Mass), With<Player>>,
+    asteroid_query: &Query<(&Transform, &Mass), With<Asteroid>>,
 ) -> Option<PlayerAsteroidCollision> {
     // Try entity1 as player, entity2 as asteroid
-    if let Ok((_, transform)) = player_query.get(entity1)
-        && let Ok((_, size)) = asteroid_query.get(entity2) {
+    if let Ok((_, transform, player_mass)) = player_query.get(entity1)
+        && let Ok((asteroid, asteroid_mass)) = asteroid_query.get(entity2) {
             return Some(PlayerAsteroidCollision {
                 player_entity: entity1,
                 position: transform.translation,
-                asteroid_size: *size,
+                asteroid_position: asteroid.translation,
+                player_mass: player_mass.0,
+                asteroid_mass: asteroid_mass.0,
             });
         }
 
     // Try entity2 as player, entity1 as asteroid
-    if let Ok((_, transform)) = player_query.get(entity2)
-        && let Ok((_, size)) = asteroid_query.get(entity1) {
+    if let Ok((_, transform, player_mass)) = player_query.get(entity2)
+        && let Ok((asteroid, asteroid_mass)) = asteroid_query.get(entity1) {
             return Some(PlayerAsteroidCollision {
                 player_entity: entity2,
                 position: transform.translation,
-                asteroid_size: *size,
+                asteroid_position: asteroid.translation,
+                player_mass: player_mass.0,
+                asteroid_mass: asteroid_mass.0,
             });
         }
 
@@ -278,12 +631,91 @@ fn check_player_asteroid_collision(
 /// This function only handles the collision response
 fn handle_collision(
     collision: PlayerAsteroidCollision,
+    impulse: f32,
+    restitution: f32,
+    tuning: &AsteroidTuning,
     message: &mut MessageWriter<DamageEvent>,
 ) {
+    // an impulse is reduced mass times the change in closing speed it caused,
+    // and the solver both stops the closing speed and bounces (1 + e) of it back
+    let reduced_mass = reduced_mass(collision.player_mass, collision.asteroid_mass);
+    let closing_speed = impulse / (reduced_mass * (1.0 + restitution));
+    let damage = tuning.impact_damage(reduced_mass, closing_speed);
+    if damage <= 0.0 {
+        return;
+    }
+
     message.write( DamageEvent {
         player: collision.player_entity,
         position: collision.position,
-        source_type: DamageSource::AsteroidEntity(collision.asteroid_size),
+        from: collision.asteroid_position,
+        source_type: DamageSource::Impact(damage),
         }
         );
     }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::resources::tests::starter_ship_and_mid_sized_rock;
+    use bevy::ecs::system::RunSystemOnce;
+
+    /// A contact the solver pushed apart with `impulse`, bouncing by `restitution`
+    fn contact(impulse: f32, restitution: f32) -> ContactPair {
+        let mut point = ContactPoint::new(Vec2::ZERO, Vec2::ZERO, Vec2::ZERO, 0.0);
+        point.normal_impulse = impulse;
+        let mut manifold = ContactManifold::new([point], Vec2::Y);
+        manifold.restitution = restitution;
+        let mut contacts = ContactPair::new(Entity::PLACEHOLDER, Entity::PLACEHOLDER);
+        contacts.manifolds.push(manifold);
+        contacts
+    }
+
+    /// The damage a mid-sized rock does meeting the starter ship at `closing_speed`
+    fn ram(world: &mut World, closing_speed: f32, restitution: f32) -> Option<f32> {
+        let (player_mass, asteroid_mass) = starter_ship_and_mid_sized_rock();
+        // a head-on contact that stops the closing speed and bounces e of it back
+        let contacts = contact(
+            reduced_mass(player_mass, asteroid_mass) * closing_speed * (1.0 + restitution),
+            restitution,
+        );
+
+        world
+            .run_system_once(move |mut message: MessageWriter<DamageEvent>| {
+                let collision = PlayerAsteroidCollision {
+                    player_entity: Entity::PLACEHOLDER,
+                    position: Vec3::ZERO,
+                    asteroid_position: Vec3::Y,
+                    player_mass,
+                    asteroid_mass,
+                };
+                handle_collision(
+                    collision,
+                    total_normal_impulse(&contacts),
+                    combined_restitution(&contacts),
+                    &AsteroidTuning::default(),
+                    &mut message,
+                );
+            })
+            .expect("handle_collision failed");
+        let mut messages = world.resource_mut::<Messages<DamageEvent>>();
+        messages.drain().last().map(|event| event.damage())
+    }
+
+    #[test]
+    fn test_bounciness_does_not_change_impact_damage() {
+        let mut world = World::new();
+        world.init_resource::<Messages<DamageEvent>>();
+        let tuning = AsteroidTuning::default();
+        let (ship, rock) = starter_ship_and_mid_sized_rock();
+
+        // iron and crystal rocks at the same speed hurt the same
+        let iron = ram(&mut world, 200.0, 0.15).unwrap();
+        let crystal = ram(&mut world, 200.0, 0.45).unwrap();
+        assert!((iron - crystal).abs() < 1e-3);
+        assert!((iron - tuning.impact_damage(reduced_mass(ship, rock), 200.0)).abs() < 1e-3);
+
+        // a slow bump stays harmless however hard it bounces
+        assert_eq!(ram(&mut world, 30.0, 0.45), None);
+    }
+}
@@ -0,0 +1,370 @@
+//! Gameplay tuning loaded from `assets/config/game.config.ron`
+//!
+//! Asteroid sizes, spawn weights, the difficulty curve and director, damage rules
+//! and ship physics can all be tuned without recompiling. On native builds the
+//! file is watched, so saving it applies the new numbers straight away (ship
+//! handling from the next run).
+//! A file with mistakes is rejected with the reason in the log and the game keeps
+//! whatever it had before.
+
+use crate::resources::{
+    AsteroidSpawnConfig, AsteroidTuning, DamageConfig, DifficultyConfig, DirectorConfig,
+    MaterialRegistry, PhysicsConfig, ShapeRegistry,
+};
+use crate::systems::level::{Campaign, CampaignHandle};
+use bevy::asset::io::Reader;
+use bevy::asset::{AssetLoader, LoadContext};
+use bevy::ecs::system::SystemParam;
+use bevy::prelude::*;
+use serde::Deserialize;
+use serde::de::DeserializeOwned;
+use std::marker::PhantomData;
+
+pub const GAME_CONFIG_PATH: &str = "config/game.config.ron";
+
+/// Everything in the config file, one section per tuning resource
+///
+/// Rust Concept: serde defaults
+/// Any section or field left out of the file keeps its Default value, and
+/// deny_unknown_fields turns a misspelled name into an error instead of a silent no-op
+#[derive(Asset, TypePath, Deserialize, Default)]
+#[serde(default, deny_unknown_fields)]
+pub struct GameConfig {
+    pub asteroids: AsteroidTuning,
+    pub spawning: AsteroidSpawnConfig,
+    pub shapes: ShapeRegistry,
+    pub materials: MaterialRegistry,
+    pub difficulty: DifficultyConfig,
+    pub director: DirectorConfig,
+    pub damage: DamageConfig,
+    pub physics: PhysicsConfig,
+    #[serde(skip)]
+    pub fingerprint: u64, // of the file it was read from
+}
+
+/// A file designers write by hand in RON, checked before the game ever sees it
+///
+/// Rust Concept: Traits with default methods
+/// Each file type only says what it's called and what makes it valid, parsing is shared
+pub trait RonAsset: Asset + DeserializeOwned {
+    /// What the file is, for error messages
+    const DESCRIPTION: &'static str;
+    const EXTENSIONS: &'static [&'static str];
+
+    /// Catch values that parse fine but would break the game
+    fn validate(&self) -> Result<(), String> {
+        Ok(())
+    }
+
+    /// Remember which version of the file this came from
+    fn set_fingerprint(&mut self, fingerprint: u64);
+
+    /// Parse and sanity-check a file's contents
+    fn parse(contents: &str) -> Result<Self, String> {
+        let mut asset: Self = ron::from_str(contents)
+            .map_err(|e| format!("could not parse {}: {e}", Self::DESCRIPTION))?;
+        asset
+            .validate()
+            .map_err(|e| format!("invalid {}: {e}", Self::DESCRIPTION))?;
+        asset.set_fingerprint(fingerprint(contents.as_bytes()));
+        Ok(asset)
+    }
+}
+
+/// A hash of a tuning file that stays the same from build to build
+///
+/// Rust Concept: Stable hashing
+/// std's DefaultHasher is allowed to change between Rust releases, but a saved
+/// replay has to hash the same next year, so this is plain FNV-1a
+pub fn fingerprint(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+        (hash ^ u64::from(*byte)).wrapping_mul(0x0100_0000_01b3)
+    })
+}
+
+/// The config and campaign the simulation is running on
+///
+/// A replay only plays out the same with the same tuning, so it records this
+/// fingerprint and is refused when the files have changed since
+#[derive(SystemParam)]
+pub struct LoadedTuning<'w> {
+    config: Res<'w, GameConfigHandle>,
+    configs: Res<'w, Assets<GameConfig>>,
+    campaign: Res<'w, CampaignHandle>,
+    campaigns: Res<'w, Assets<Campaign>>,
+}
+
+impl LoadedTuning<'_> {
+    /// Both files at once, a file that failed to load counts as 0 (the defaults)
+    pub fn fingerprint(&self) -> u64 {
+        let config = self
+            .configs
+            .get(&self.config.0)
+            .map_or(0, |c| c.fingerprint);
+        let campaign = self
+            .campaigns
+            .get(&self.campaign.0)
+            .map_or(0, |c| c.fingerprint);
+        fingerprint(&[config.to_le_bytes(), campaign.to_le_bytes()].concat())
+    }
+}
+
+impl RonAsset for GameConfig {
+    const DESCRIPTION: &'static str = "game config";
+    const EXTENSIONS: &'static [&'static str] = &["config.ron"];
+
+    fn set_fingerprint(&mut self, fingerprint: u64) {
+        self.fingerprint = fingerprint;
+    }
+
+    /// Reports every problem at once so a designer can fix them in one go
+    fn validate(&self) -> Result<(), String> {
+        let mut problems = Vec::new();
+        let mut check = |ok: bool, problem: &str| {
+            if !ok {
+                problems.push(problem.to_string());
+            }
+        };
+
+        let asteroids = &self.asteroids;
+        check(asteroids.density > 0.0, "asteroids.density must be above 0");
+        check(
+            asteroids.hit_points_per_mass > 0.0,
+            "asteroids.hit_points_per_mass must be above 0",
+        );
+        check(
+            asteroids.impact_energy_per_damage > 0.0,
+            "asteroids.impact_energy_per_damage must be above 0",
+        );
+        check(
+            asteroids.min_impact_speed >= 0.0 && asteroids.max_impact_damage >= 0.0,
+            "asteroids.min_impact_speed and max_impact_damage can't be negative",
+        );
+
+        let spawning = &self.spawning;
+        check(
+            spawning.min_radius > 0.0,
+            "spawning.min_radius must be above 0",
+        );
+        check(
+            spawning.min_radius <= spawning.max_radius,
+            "spawning.min_radius can't be more than max_radius",
+        );
+        check(
+            spawning.min_speed < spawning.max_speed,
+            "spawning.min_speed must be less than max_speed",
+        );
+        check(
+            spawning.size_bias > 0.0,
+            "spawning.size_bias must be above 0",
+        );
+        check(
+            spawning.spawn_x_range > 0.0 && spawning.spawn_y_range > 0.0,
+            "spawning.spawn_x_range and spawn_y_range must be above 0",
+        );
+        check(
+            spawning.spawn_x > 0.0 && spawning.spawn_y > 0.0,
+            "spawning.spawn_x and spawn_y must be above 0",
+        );
+        check(
+            spawning.edges.iter().any(|(_, weight)| *weight > 0),
+            "spawning.edges needs at least one weight above 0",
+        );
+        check(
+            (0.0..=1.0).contains(&spawning.aim_chance),
+            "spawning.aim_chance must be between 0 and 1",
+        );
+        check(
+            spawning.cleanup_distance > 0.0,
+            "spawning.cleanup_distance must be above 0",
+        );
+
+        check(
+            self.shapes.weights.iter().any(|(_, weight)| *weight > 0),
+            "shapes needs at least one weight above 0",
+        );
+        check(
+            self.materials.weights.iter().any(|(_, weight)| *weight > 0),
+            "materials needs at least one weight above 0",
+        );
+
+        let difficulty = &self.difficulty;
+        check(
+            difficulty.min_interval > 0.0,
+            "difficulty.min_interval must be above 0",
+        );
+        check(
+            difficulty.initial_interval >= difficulty.min_interval,
+            "difficulty.initial_interval can't be less than min_interval",
+        );
+        check(
+            difficulty.curve_steepness >= 0.0,
+            "difficulty.curve_steepness can't be negative",
+        );
+
+        let director = &self.director;
+        check(director.window > 0.0, "director.window must be above 0");
+        check(
+            director.response >= 0.0,
+            "director.response can't be negative",
+        );
+        check(
+            (0.0..=1.0).contains(&director.start_intensity),
+            "director.start_intensity must be between 0 and 1",
+        );
+        check(
+            director.target_kills_per_minute > 0.0
+                && director.target_accuracy > 0.0
+                && director.damage_tolerance > 0.0,
+            "director targets must be above 0",
+        );
+        check(
+            [
+                director.interval_scale,
+                director.speed_scale,
+                director.size_bias_scale,
+            ]
+            .iter()
+            .all(|(easiest, hardest)| *easiest > 0.0 && *hardest > 0.0),
+            "director scales must be above 0",
+        );
+
+        let damage = &self.damage;
+        check(
+            damage.invulnerability >= 0.0
+                && damage.contact_damage_per_second >= 0.0
+                && damage.repair_per_level >= 0.0,
+            "damage.invulnerability, contact_damage_per_second and repair_per_level can't be negative",
+        );
+        check(damage.blink_rate > 0.0, "damage.blink_rate must be above 0");
+
+        check(
+            self.physics.projectile_lifetime > 0.0,
+            "physics.projectile_lifetime must be above 0",
+        );
+
+        if problems.is_empty() {
+            Ok(())
+        } else {
+            Err(problems.join("; "))
+        }
+    }
+}
+
+/// Teaches the asset server to read one kind of RON file
+///
+/// Rust Concept: Async trait methods
+/// Assets load on a background task, so load() is async and awaits the file's bytes.
+/// An Err is logged by the asset server along with the file's path.
+#[derive(TypePath)]
+pub struct RonLoader<T: RonAsset>(PhantomData<T>);
+
+// derive(Default) would insist on T: Default too
+impl<T: RonAsset> Default for RonLoader<T> {
+    fn default() -> Self {
+        Self(PhantomData)
+    }
+}
+
+impl<T: RonAsset> AssetLoader for RonLoader<T> {
+    type Asset = T;
+    type Settings = ();
+    type Error = String;
+
+    async fn load(
+        &self,
+        reader: &mut dyn Reader,
+        _settings: &(),
+        _load_context: &mut LoadContext<'_>,
+    ) -> Result<T, String> {
+        let mut bytes = Vec::new();
+        reader
+            .read_to_end(&mut bytes)
+            .await
+            .map_err(|e| format!("could not read {}: {e}", T::DESCRIPTION))?;
+        let contents = std::str::from_utf8(&bytes)
+            .map_err(|e| format!("{} isn't UTF-8: {e}", T::DESCRIPTION))?;
+        T::parse(contents)
+    }
+
+    fn extensions(&self) -> &[&str] {
+        T::EXTENSIONS
+    }
+}
+
+/// Keeps the config loaded (and watched) for the whole session
+#[derive(Resource)]
+pub struct GameConfigHandle(pub Handle<GameConfig>);
+
+/// Copy the config into the tuning resources whenever it loads or changes on disk
+#[allow(clippy::too_many_arguments)]
+pub fn apply_game_config(
+    mut events: MessageReader<AssetEvent<GameConfig>>,
+    configs: Res<Assets<GameConfig>>,
+    mut tuning: ResMut<AsteroidTuning>,
+    mut spawning: ResMut<AsteroidSpawnConfig>,
+    mut shapes: ResMut<ShapeRegistry>,
+    mut materials: ResMut<MaterialRegistry>,
+    mut difficulty: ResMut<DifficultyConfig>,
+    mut director: ResMut<DirectorConfig>,
+    mut damage: ResMut<DamageConfig>,
+    mut physics: ResMut<PhysicsConfig>,
+) {
+    for event in events.read() {
+        let (AssetEvent::LoadedWithDependencies { id } | AssetEvent::Modified { id }) = event
+        else {
+            continue;
+        };
+        let Some(config) = configs.get(*id) else {
+            continue;
+        };
+
+        *tuning = config.asteroids.clone();
+        *spawning = config.spawning.clone();
+        *shapes = config.shapes.clone();
+        *materials = config.materials.clone();
+        *difficulty = config.difficulty.clone();
+        *director = config.director.clone();
+        *damage = config.damage.clone();
+        *physics = config.physics.clone();
+        info!("Applied game config");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_shipped_config_is_valid() {
+        let contents = include_str!("../../assets/config/game.config.ron");
+        if let Err(e) = GameConfig::parse(contents) {
+            panic!("{e}");
+        }
+    }
+
+    #[test]
+    fn test_bad_configs_are_explained() {
+        // missing sections keep their defaults
+        assert!(GameConfig::parse("(spawning: (max_speed: 300.0))").is_ok());
+
+        let typo = GameConfig::parse("(spawning: (max_sped: 300.0))")
+            .err()
+            .unwrap();
+        assert!(typo.contains("max_sped"), "{typo}");
+
+        let backwards = GameConfig::parse("(spawning: (min_radius: 60.0, max_radius: 20.0))")
+            .err()
+            .unwrap();
+        assert!(backwards.contains("min_radius"), "{backwards}");
+    }
+
+    #[test]
+    fn test_any_edit_changes_the_fingerprint() {
+        let before = GameConfig::parse("(spawning: (max_speed: 300.0))").unwrap();
+        let after = GameConfig::parse("(spawning: (max_speed: 301.0))").unwrap();
+        assert_ne!(before.fingerprint, after.fingerprint);
+        // the known FNV-1a value, so it can't quietly change between builds
+        assert_eq!(fingerprint(b"a"), 0xaf63_dc4c_8601_ec8c);
+    }
+}
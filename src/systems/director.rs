@@ -0,0 +1,107 @@
+//! The difficulty director
+//!
+//! Watches how the player is coping (kills, accuracy, damage taken and health)
+//! and eases the spawning up or down within the bounds in the config's director
+//! section. It runs in the simulation, so a replay sees exactly the same difficulty.
+//! F3 shows what it's thinking, for tuning.
+
+use crate::components::{Health, Player};
+use crate::events::{AsteroidDestroyed, HealthChanged, ProjectileHit};
+use crate::resources::{Director, DirectorConfig, DirectorSample};
+use bevy::prelude::*;
+
+/// Fold this tick's play into the director
+pub fn update_director(
+    mut director: ResMut<Director>,
+    config: Res<DirectorConfig>,
+    mut projectile_hits: MessageReader<ProjectileHit>,
+    mut destroyed: MessageReader<AsteroidDestroyed>,
+    mut health_changes: MessageReader<HealthChanged>,
+    player: Query<&Health, With<Player>>,
+    time: Res<Time>,
+) {
+    let hits: f32 = projectile_hits.read().map(|hit| hit.share).sum();
+    let kills = destroyed.read().count() as u32;
+    // repairs don't make up for being hit, and rocks being shot don't count at all
+    let damage: f32 = health_changes
+        .read()
+        .filter(|change| change.cause.is_some() && player.contains(change.entity))
+        .map(|change| -change.change())
+        .sum();
+
+    // nothing to judge between death and the game over screen
+    let Ok(health) = player.single() else {
+        return;
+    };
+    let shots = std::mem::take(&mut director.shots_fired);
+    director.observe(
+        &config,
+        time.delta_secs(),
+        DirectorSample {
+            kills,
+            shots,
+            hits,
+            damage,
+            health: health.current() / health.max(),
+        },
+    );
+}
+
+/// Marker component for the director readout
+#[derive(Component)]
+pub(crate) struct DirectorDisplay;
+
+/// The director readout, hidden until F3
+pub fn setup_director_display(mut commands: Commands) {
+    commands.spawn((
+        Text::new(""),
+        TextFont {
+            font_size: 14.0,
+            ..default()
+        },
+        TextColor(Color::srgb(0.6, 0.9, 0.6)),
+        Node {
+            position_type: PositionType::Absolute,
+            bottom: Val::Px(10.0),
+            left: Val::Px(10.0),
+            ..default()
+        },
+        Visibility::Hidden,
+        DirectorDisplay,
+    ));
+}
+
+/// F3 toggles the readout, which shows every number the director works from
+pub fn update_director_display(
+    keyboard: Res<ButtonInput<KeyCode>>,
+    director: Res<Director>,
+    config: Res<DirectorConfig>,
+    mut display: Query<(&mut Text, &mut Visibility), With<DirectorDisplay>>,
+) {
+    let Ok((mut text, mut visibility)) = display.single_mut() else {
+        return;
+    };
+
+    if keyboard.just_pressed(KeyCode::F3) {
+        visibility.toggle_visible_hidden();
+    }
+    if *visibility == Visibility::Hidden {
+        return;
+    }
+
+    let status = if config.enabled { "" } else { " (off)" };
+    *text = Text::new(format!(
+        "Director{status}  intensity {:.2}  performance {:+.2}\n\
+         kills/min {:.1}  accuracy {:.0}%  damage/s {:.1}  hull {:.0}%\n\
+         spawn interval x{:.2}  speed x{:.2}  size bias x{:.2}",
+        director.intensity,
+        director.performance,
+        director.kills_per_minute,
+        director.accuracy(&config) * 100.0,
+        director.damage_per_second,
+        director.health * 100.0,
+        director.interval_scale,
+        director.speed_scale,
+        director.size_bias_scale,
+    ));
+}
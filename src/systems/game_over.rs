@@ -1,9 +1,29 @@
 use crate::events::*;
-use crate::resources::AppState;
+use crate::resources::{AppState, GameData, GameRng, HighScores, NameEntry, RunMode};
+use crate::systems::high_score::{NameEntryText, save_high_scores};
 use bevy::prelude::*;
 
+const SAVE_BUTTON_COLOR: Color = Color::srgb(0.15, 0.15, 0.25);
+
+/// Saves the name being typed, for players who can only tap
+#[derive(Component)]
+pub(crate) struct SaveScoreButton;
+
 // This spawns the GameOver UI
-pub fn setup_game_over(mut commands: Commands) {
+pub fn setup_game_over(
+    mut commands: Commands,
+    game_data: Res<GameData>,
+    high_scores: Res<HighScores>,
+    game_rng: Res<GameRng>,
+    run_mode: Res<RunMode>,
+) {
+    // only ask for a name if a live run made the table
+    let new_high_score = !run_mode.is_replay() && high_scores.qualifies(game_data.score);
+    commands.insert_resource(NameEntry {
+        name: String::new(),
+        active: new_high_score,
+    });
+
     // spawn a root node that covers the screen, flexbox container
     commands
         .spawn((
@@ -18,13 +38,68 @@ pub fn setup_game_over(mut commands: Commands) {
             DespawnOnExit(AppState::GameOver),
         ))
         .with_children(|parent| {
+            if new_high_score {
+                parent.spawn((
+                    Text::new(format!(
+                        "New high score: {}!\nType your name and press <Enter>",
+                        game_data.score
+                    )),
+                    TextFont {
+                        font_size: 24.0,
+                        ..default()
+                    },
+                    TextColor(Color::WHITE),
+                ));
+                parent.spawn((
+                    Text::new("_"),
+                    TextFont {
+                        font_size: 32.0,
+                        ..default()
+                    },
+                    TextColor(Color::srgb(1.0, 0.8, 0.2)),
+                    NameEntryText,
+                ));
+                // a stray tap anywhere else mustn't throw the name away
+                parent
+                    .spawn((
+                        Button,
+                        Node {
+                            margin: UiRect::top(Val::Px(12.0)),
+                            padding: UiRect::axes(Val::Px(24.0), Val::Px(8.0)),
+                            ..default()
+                        },
+                        BackgroundColor(SAVE_BUTTON_COLOR),
+                        SaveScoreButton,
+                    ))
+                    .with_children(|button| {
+                        button.spawn((
+                            Text::new("Save"),
+                            TextFont {
+                                font_size: 24.0,
+                                ..default()
+                            },
+                            TextColor(Color::WHITE),
+                        ));
+                    });
+            } else {
+                parent.spawn((
+                    Text::new("Have you tried...\nbeing better?\nPress <Enter> ya filthy animal"),
+                    TextFont {
+                        font_size: 24.0,
+                        ..default()
+                    },
+                    TextColor(Color::WHITE),
+                ));
+            }
+
+            // so a teammate can fly the same field
             parent.spawn((
-                Text::new("Have you tried...\nbeing better?\nPress <Enter> ya filthy animal"),
+                Text::new(format!("Seed: {}", game_rng.seed())),
                 TextFont {
-                    font_size: 24.0,
+                    font_size: 18.0,
                     ..default()
                 },
-                TextColor(Color::WHITE),
+                TextColor(Color::srgb(0.8, 0.8, 0.9)),
             ));
         });
     // Add text children for title and instructions
@@ -36,11 +111,34 @@ pub fn setup_game_over(mut commands: Commands) {
 // state
 pub fn handle_game_over_input(
     keyboard: Res<ButtonInput<KeyCode>>,
+    touches: Res<Touches>,
     mut next_state: ResMut<NextState<AppState>>,
+    save_button: Query<&Interaction, (Changed<Interaction>, With<SaveScoreButton>)>,
     mut message: MessageWriter<PlaySoundEvent>,
+    mut name_entry: ResMut<NameEntry>,
+    mut high_scores: ResMut<HighScores>,
+    game_data: Res<GameData>,
 ) {
-    // Check for Enter Key, Transition to Menu
-    if keyboard.just_pressed(KeyCode::Enter) {
+    // Check for Enter Key (or a tap), Transition to Menu
+    // while a name is being entered only the Save button counts, without a
+    // keyboard that saves the score as Anonymous
+    let tapped = if name_entry.active {
+        save_button
+            .iter()
+            .any(|interaction| *interaction == Interaction::Pressed)
+    } else {
+        touches.any_just_pressed()
+    };
+    if keyboard.just_pressed(KeyCode::Enter) || tapped {
+        if name_entry.active {
+            let name = match name_entry.name.trim() {
+                "" => "Anonymous".to_string(),
+                name => name.to_string(),
+            };
+            high_scores.insert(name, game_data.score);
+            save_high_scores(&high_scores);
+            name_entry.active = false;
+        }
         message.write(PlaySoundEvent::MenuBoop); // this will also play when entering name
         next_state.set(AppState::Menu);
     }
@@ -1,20 +1,97 @@
-use crate::components::{Health, Player};
+use crate::components::{DamageSource, Health, Invulnerability, Player, Resistances, Shield};
 use crate::events::*;
-use crate::resources::AppState;
+use crate::resources::{AppState, DamageConfig};
 use crate::systems::collision::spawn_explosion;
 use bevy::prelude::*;
 use bevy_hanabi::prelude::*;
 
+/// Take damage off anything with health, after its armour
+///
+/// The ship and the asteroids both go through here, so resistances always apply
+/// and every real change is reported. Returns the damage that got through
+#[allow(clippy::too_many_arguments)]
+pub fn apply_damage(
+    entity: Entity,
+    health: &mut Health,
+    resistances: &Resistances,
+    source: DamageSource,
+    damage: f32, // what's left of the source's damage after falloff and shields
+    position: Vec3,
+    from: Vec3,
+    health_message: &mut MessageWriter<HealthChanged>,
+) -> f32 {
+    let previous = health.current();
+    health.damage(resistances.reduce(source.kind(), damage));
+    let dealt = previous - health.current();
+    // shrugged off completely, nothing changed to tell anyone about
+    if dealt > 0.0 {
+        health_message.write(HealthChanged {
+            entity,
+            previous,
+            new_health: health.current(),
+            max_health: health.max(),
+            position,
+            from,
+            cause: Some(source),
+        });
+    }
+    dealt
+}
+
+/// Apply damage and repairs to the ship, the one place its health changes
+#[allow(clippy::too_many_arguments)]
 pub fn handle_health_message(
     mut events: MessageReader<DamageEvent>,
-    // mut health_message: MessageWriter<HealthChanged>,
+    mut heals: MessageReader<HealEvent>,
+    mut health_message: MessageWriter<HealthChanged>,
     mut death_message: MessageWriter<DeathEvent>,
     mut audio_message: MessageWriter<PlaySoundEvent>,
-    mut player_query: Query<&mut Health, With<Player>>,
+    mut player_query: Query<
+        (&mut Health, &mut Invulnerability, &Resistances, &Transform),
+        With<Player>,
+    >,
+    mut shield_query: Query<&mut Shield>,
+    config: Res<DamageConfig>,
 ) {
     for event in events.read() {
-        let mut health = player_query.single_mut().unwrap();
-        health.damage(event.source_type.damage());
+        let mut damage = event.damage();
+        let hit = event.source_type.is_hit();
+        let (mut health, mut invulnerability, resistances, _) = player_query.single_mut().unwrap();
+
+        // nothing gets through just after a hit, so one crash can't count twice,
+        // and a wreck can't die twice
+        if health.is_dead() || invulnerability.is_active() || damage <= 0.0 {
+            continue;
+        }
+        // the grace starts with any hit that lands, on the shield or on the hull
+        if hit {
+            invulnerability.start(config.invulnerability);
+        }
+
+        // a shield takes the hit first, only what it can't hold gets through
+        if let Ok(mut shield) = shield_query.single_mut()
+            && !shield.is_down()
+        {
+            damage = shield.absorb(damage);
+            if hit {
+                audio_message.write(PlaySoundEvent::ShieldHit);
+            }
+            if damage <= 0.0 {
+                continue;
+            }
+        }
+
+        // then the hull's own armour
+        apply_damage(
+            event.player,
+            &mut health,
+            resistances,
+            event.source_type,
+            damage,
+            event.position,
+            event.from,
+            &mut health_message,
+        );
 
         if health.is_dead() {
             audio_message.write(PlaySoundEvent::GameOver);
@@ -22,13 +99,45 @@ pub fn handle_health_message(
                 player: event.player,
                 position: event.position,
             });
-        } else {
+        } else if hit {
             audio_message.write(PlaySoundEvent::Bonk);
-            // health_message.write(HealthChanged {
-            //     player: event.player,
-            //     new_health: health.current(),
-            //     position: event.position,
-            // });
+        }
+    }
+
+    for heal in heals.read() {
+        let Ok((mut health, _, _, transform)) = player_query.get_mut(heal.player) else {
+            continue;
+        };
+        let previous = health.current();
+        health.heal(heal.amount);
+        if health.current() != previous {
+            health_message.write(HealthChanged {
+                entity: heal.player,
+                previous,
+                new_health: health.current(),
+                max_health: health.max(),
+                position: transform.translation,
+                from: transform.translation,
+                cause: None,
+            });
+        }
+    }
+}
+
+/// Count the grace after a hit down, blinking the ship until it's over
+pub fn update_invulnerability(
+    time: Res<Time>,
+    config: Res<DamageConfig>,
+    mut player_query: Query<(&mut Invulnerability, &mut Sprite), With<Player>>,
+) {
+    for (mut invulnerability, mut sprite) in player_query.iter_mut() {
+        invulnerability.timer.tick(time.delta());
+
+        let blink = invulnerability.is_active()
+            && (invulnerability.timer.elapsed_secs() * config.blink_rate).fract() < 0.5;
+        let alpha = if blink { 0.25 } else { 1.0 };
+        if sprite.color.alpha() != alpha {
+            sprite.color.set_alpha(alpha);
         }
     }
 }
@@ -41,9 +150,101 @@ pub fn handle_death_message(
 ) {
     for event in events.read() {
         commands.entity(event.player).despawn();
-        spawn_explosion(&mut commands, &mut effects, event.position);
+        // the death explosion should still be going off behind the GameOver screen
+        let explosion = spawn_explosion(&mut commands, &mut effects, event.position);
+        commands
+            .entity(explosion)
+            .insert(DespawnOnExit(AppState::GameOver));
         next_state.set(AppState::GameOver);
         //despawn
         // transition to GameOver
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::components::DamageSource;
+    use bevy::ecs::system::RunSystemOnce;
+
+    #[test]
+    fn test_shield_hit_starts_the_grace() {
+        let mut world = World::new();
+        world.init_resource::<DamageConfig>();
+        world.init_resource::<Messages<DamageEvent>>();
+        world.init_resource::<Messages<HealEvent>>();
+        world.init_resource::<Messages<HealthChanged>>();
+        world.init_resource::<Messages<DeathEvent>>();
+        world.init_resource::<Messages<PlaySoundEvent>>();
+        let player = world
+            .spawn((
+                Player,
+                Health::new(100.0),
+                Invulnerability::default(),
+                Resistances::default(),
+                Transform::default(),
+            ))
+            .id();
+        let shield = world.spawn(Shield::new(50.0, 10.0)).id();
+
+        // two rocks in the same tick, the shield soaks up the first whole
+        for _ in 0..2 {
+            world.write_message(DamageEvent {
+                player,
+                position: Vec3::ZERO,
+                from: Vec3::Y,
+                source_type: DamageSource::Impact(20.0),
+            });
+        }
+        world
+            .run_system_once(handle_health_message)
+            .expect("handle_health_message failed");
+
+        assert!(world.get::<Invulnerability>(player).unwrap().is_active());
+        assert_eq!(world.get::<Shield>(shield).unwrap().strength(), 0.6);
+        assert_eq!(world.get::<Health>(player).unwrap().current(), 100.0);
+    }
+
+    #[test]
+    fn test_fully_resisted_damage_is_silent() {
+        let mut world = World::new();
+        world.init_resource::<Messages<HealthChanged>>();
+
+        world
+            .run_system_once(|mut health_message: MessageWriter<HealthChanged>| {
+                let armour = Resistances {
+                    hazard: 1.0,
+                    ..default()
+                };
+                let mut health = Health::new(50.0);
+                apply_damage(
+                    Entity::PLACEHOLDER,
+                    &mut health,
+                    &armour,
+                    DamageSource::Hazard(30.0),
+                    30.0,
+                    Vec3::ZERO,
+                    Vec3::Y,
+                    &mut health_message,
+                );
+                apply_damage(
+                    Entity::PLACEHOLDER,
+                    &mut health,
+                    &Resistances::default(),
+                    DamageSource::Hazard(30.0),
+                    30.0,
+                    Vec3::ZERO,
+                    Vec3::Y,
+                    &mut health_message,
+                );
+            })
+            .expect("apply_damage failed");
+
+        let changes: Vec<_> = world
+            .resource_mut::<Messages<HealthChanged>>()
+            .drain()
+            .collect();
+        assert_eq!(changes.len(), 1);
+        assert_eq!((changes[0].previous, changes[0].new_health), (50.0, 20.0));
+    }
+}
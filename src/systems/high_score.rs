@@ -0,0 +1,171 @@
+//! High-score table persistence and name entry
+
+use crate::resources::{HighScores, MAX_HIGH_SCORES, MAX_NAME_LENGTH, NameEntry};
+use crate::storage;
+use bevy::input::ButtonState;
+use bevy::input::keyboard::KeyboardInput;
+use bevy::prelude::*;
+
+const HIGH_SCORE_KEY: &str = "high_scores.txt";
+
+/// Marker component for the name being typed on the GameOver screen
+#[derive(Component)]
+pub(crate) struct NameEntryText;
+
+/// Load the saved table at startup, missing or unreadable data means an empty table
+pub fn load_high_scores(mut high_scores: ResMut<HighScores>) {
+    if let Some(contents) = storage::read(HIGH_SCORE_KEY) {
+        *high_scores = decode_scores(&contents);
+        info!("Loaded {} high scores", high_scores.scores.len());
+    }
+}
+
+/// Write the table back to storage
+///
+/// Rust Concept: Logging instead of panicking
+/// Losing a save is annoying, crashing the game over it is worse
+pub fn save_high_scores(high_scores: &HighScores) {
+    if let Err(e) = storage::write(HIGH_SCORE_KEY, &encode_scores(high_scores)) {
+        warn!("Could not save high scores: {e}");
+    }
+}
+
+/// Type a name on the GameOver screen
+/// Enter is handled by handle_game_over_input, this only edits the text
+pub fn handle_name_entry(
+    mut key_events: MessageReader<KeyboardInput>,
+    mut name_entry: ResMut<NameEntry>,
+    mut text_query: Query<&mut Text, With<NameEntryText>>,
+) {
+    if !name_entry.active {
+        key_events.clear();
+        return;
+    }
+
+    for event in key_events.read() {
+        if event.state != ButtonState::Pressed {
+            continue;
+        }
+
+        if event.key_code == KeyCode::Backspace {
+            name_entry.name.pop();
+            continue;
+        }
+
+        // Rust Concept: Option chaining
+        // keys like shift or arrows have no text and are skipped
+        let Some(text) = &event.text else {
+            continue;
+        };
+        for c in text.chars().filter(|c| is_name_char(*c)) {
+            if name_entry.name.chars().count() < MAX_NAME_LENGTH {
+                name_entry.name.push(c);
+            }
+        }
+    }
+
+    if let Ok(mut text) = text_query.single_mut() {
+        *text = Text::new(format!("{}_", name_entry.name));
+    }
+}
+
+/// Render the table as lines of text for the menu
+pub fn high_score_table_text(high_scores: &HighScores) -> String {
+    if high_scores.scores.is_empty() {
+        return "No high scores yet".to_string();
+    }
+
+    let rows: Vec<String> = high_scores
+        .scores
+        .iter()
+        .enumerate()
+        .map(|(i, (name, score))| {
+            format!(
+                "{:>2}. {:<width$} {:>7}",
+                i + 1,
+                name,
+                score,
+                width = MAX_NAME_LENGTH
+            )
+        })
+        .collect();
+    format!("High Scores\n{}", rows.join("\n"))
+}
+
+// letters, digits, spaces and a bit of punctuation. keeps the save format trivial
+fn is_name_char(c: char) -> bool {
+    c.is_alphanumeric() || c == ' ' || c == '-' || c == '_' || c == '.'
+}
+
+// one "score<TAB>name" per line
+fn encode_scores(high_scores: &HighScores) -> String {
+    high_scores
+        .scores
+        .iter()
+        .map(|(name, score)| format!("{score}\t{name}\n"))
+        .collect()
+}
+
+// bad lines are skipped rather than throwing the whole table away
+fn decode_scores(contents: &str) -> HighScores {
+    let mut scores: Vec<(String, u32)> = contents
+        .lines()
+        .filter_map(|line| {
+            let (score, name) = line.split_once('\t')?;
+            Some((name.to_string(), score.trim().parse().ok()?))
+        })
+        .collect();
+
+    // Rust Concept: Stable sort
+    // equal scores keep their saved order
+    scores.sort_by(|a, b| b.1.cmp(&a.1));
+    scores.truncate(MAX_HIGH_SCORES);
+    HighScores { scores }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_insert_keeps_rank_order() {
+        let mut high_scores = HighScores::default();
+        assert_eq!(high_scores.insert("low".to_string(), 100), Some(0));
+        assert_eq!(high_scores.insert("high".to_string(), 500), Some(0));
+        assert_eq!(high_scores.insert("tie".to_string(), 100), Some(2));
+
+        let names: Vec<&str> = high_scores.scores.iter().map(|(n, _)| n.as_str()).collect();
+        assert_eq!(names, ["high", "low", "tie"]);
+    }
+
+    #[test]
+    fn test_full_table_rejects_low_scores() {
+        let mut high_scores = HighScores::default();
+        for i in 1..=MAX_HIGH_SCORES as u32 {
+            high_scores.insert(format!("p{i}"), i * 10);
+        }
+
+        assert!(!high_scores.qualifies(10));
+        assert_eq!(high_scores.insert("nope".to_string(), 5), None);
+        assert_eq!(
+            high_scores.insert("yes".to_string(), 15),
+            Some(MAX_HIGH_SCORES - 1)
+        );
+        assert_eq!(high_scores.scores.len(), MAX_HIGH_SCORES);
+        assert!(!high_scores.qualifies(0));
+    }
+
+    #[test]
+    fn test_encode_decode_round_trip() {
+        let mut high_scores = HighScores::default();
+        high_scores.insert("Ace Pilot".to_string(), 1200);
+        high_scores.insert("rookie".to_string(), 40);
+
+        assert_eq!(decode_scores(&encode_scores(&high_scores)), high_scores);
+        // garbage lines are ignored
+        assert_eq!(
+            decode_scores("not a score\nabc\tname\n"),
+            HighScores::default()
+        );
+    }
+}
@@ -0,0 +1,370 @@
+//! The hull readout and damage feedback
+//!
+//! A health bar that drains after a hit and turns red as the hull runs out,
+//! pulsing with a warning sound once it's low. Every hit flashes a red vignette
+//! around the screen and points an arrow out from the ship towards where it came
+//! from. All of it runs per frame off the simulation's messages and never feeds
+//! back into it, so replays play out the same.
+
+use crate::components::{Health, Player};
+use crate::events::{HealthChanged, PlaySoundEvent};
+use crate::resources::AppState;
+use bevy::prelude::*;
+use std::f32::consts::TAU;
+
+const BAR_WIDTH: f32 = 220.0;
+const BAR_HEIGHT: f32 = 18.0;
+/// Below this fraction of the hull the bar pulses and the warning sounds
+const LOW_HEALTH: f32 = 0.3;
+/// How long the chunk just lost hangs on before it drains away
+const DRAIN_DELAY: f32 = 0.4;
+/// Bar fractions per second
+const DRAIN_SPEED: f32 = 0.6;
+const FILL_SPEED: f32 = 0.8;
+/// Vignette strength lost per second, a full flash is gone in under half a second
+const VIGNETTE_FADE: f32 = 2.5;
+/// Alpha of each vignette ring from the screen's edge in, times the flash
+const VIGNETTE_RINGS: [f32; 4] = [0.6, 0.35, 0.2, 0.08];
+const VIGNETTE_RING_WIDTH: f32 = 14.0;
+const INDICATOR_SECS: f32 = 0.8;
+const INDICATOR_DISTANCE: f32 = 48.0;
+
+/// The hull bar, and what it's showing right now
+#[derive(Component, Default)]
+pub(crate) struct HealthBar {
+    shown: f32,    // the bright part, drops straight away and fills back up smoothly
+    trailing: f32, // the chunk just lost, drains down to shown after a moment
+    hold: f32,     // seconds until it starts draining
+}
+
+impl HealthBar {
+    fn follow(&mut self, target: f32, dt: f32) {
+        if target < self.shown {
+            self.shown = target;
+            self.hold = DRAIN_DELAY;
+        } else {
+            self.shown = (self.shown + FILL_SPEED * dt).min(target);
+        }
+
+        self.hold -= dt;
+        if self.hold <= 0.0 {
+            self.trailing -= DRAIN_SPEED * dt;
+        }
+        self.trailing = self.trailing.max(self.shown);
+    }
+}
+
+/// Marker for the bright part of the bar
+#[derive(Component)]
+pub(crate) struct HealthBarFill;
+
+/// Marker for the chunk of the bar just lost
+#[derive(Component)]
+pub(crate) struct HealthBarTrail;
+
+/// The hull bar, top left above the weapon
+pub fn setup_health_bar(mut commands: Commands) {
+    let layer = |width: f32| Node {
+        position_type: PositionType::Absolute,
+        left: Val::Px(0.0),
+        width: Val::Percent(width),
+        height: Val::Percent(100.0),
+        ..default()
+    };
+
+    commands
+        .spawn((
+            Node {
+                position_type: PositionType::Absolute,
+                top: Val::Px(14.0),
+                left: Val::Px(10.0),
+                width: Val::Px(BAR_WIDTH),
+                height: Val::Px(BAR_HEIGHT),
+                border: UiRect::all(Val::Px(2.0)),
+                ..default()
+            },
+            BackgroundColor(Color::srgba(0.0, 0.0, 0.0, 0.5)),
+            BorderColor::all(Color::srgb(0.8, 0.8, 0.9)),
+            HealthBar::default(),
+        ))
+        .with_children(|bar| {
+            // Rust Concept: Layered UI
+            // Later children draw on top, so the trail only shows past the end of the fill
+            bar.spawn((
+                layer(0.0),
+                BackgroundColor(Color::srgb(0.95, 0.9, 0.8)),
+                HealthBarTrail,
+            ));
+            bar.spawn((layer(0.0), BackgroundColor(Color::NONE), HealthBarFill));
+        });
+}
+
+/// Follow the ship's health: snap down on a hit, drain the lost chunk after a
+/// moment, and fill back up smoothly after a repair or at the start of a run
+#[allow(clippy::type_complexity)]
+pub fn update_health_bar(
+    time: Res<Time>,
+    player_query: Query<&Health, With<Player>>,
+    mut bar_query: Query<(&mut HealthBar, &mut BorderColor)>,
+    mut fill_query: Query<(&mut Node, &mut BackgroundColor), With<HealthBarFill>>,
+    mut trail_query: Query<&mut Node, (With<HealthBarTrail>, Without<HealthBarFill>)>,
+) {
+    let Ok((mut bar, mut border)) = bar_query.single_mut() else {
+        return;
+    };
+    // a wrecked ship has nothing left
+    let target = player_query.single().map_or(0.0, Health::fraction);
+    bar.follow(target, time.delta_secs());
+
+    // green through yellow to red as the hull runs out
+    let mut lightness = 0.45;
+    let mut frame = Color::srgb(0.8, 0.8, 0.9);
+    if bar.shown > 0.0 && bar.shown < LOW_HEALTH {
+        // twice a second while it's low
+        let pulse = 0.5 + 0.5 * (time.elapsed_secs() * TAU * 2.0).sin();
+        lightness += 0.25 * pulse;
+        frame = Color::srgb(1.0, 0.2 + 0.6 * pulse, 0.2 + 0.6 * pulse);
+    }
+    *border = BorderColor::all(frame);
+
+    if let Ok((mut node, mut color)) = fill_query.single_mut() {
+        node.width = Val::Percent(bar.shown * 100.0);
+        *color = BackgroundColor(Color::hsl(120.0 * bar.shown, 0.8, lightness));
+    }
+    if let Ok(mut node) = trail_query.single_mut() {
+        node.width = Val::Percent(bar.trailing * 100.0);
+    }
+}
+
+/// Sound the alarm as the hull drops into the red
+pub fn warn_low_health(
+    mut changes: MessageReader<HealthChanged>,
+    player: Query<(), With<Player>>,
+    mut audio_message: MessageWriter<PlaySoundEvent>,
+) {
+    for change in changes.read() {
+        if player.contains(change.entity) && drops_into_the_red(change) {
+            audio_message.write(PlaySoundEvent::LowHealth);
+        }
+    }
+}
+
+// only the change that crosses the line, not every hit after it
+fn drops_into_the_red(change: &HealthChanged) -> bool {
+    let before = change.previous / change.max_health;
+    let after = change.new_health / change.max_health;
+    before >= LOW_HEALTH && after < LOW_HEALTH && after > 0.0
+}
+
+/// The red glow around the edges of the screen after a hit
+#[derive(Component, Default)]
+pub(crate) struct DamageVignette {
+    flash: f32, // 0 is clear, 1 is the hardest hit
+}
+
+/// One ring of the vignette, with its alpha at full flash
+#[derive(Component)]
+pub(crate) struct VignetteRing(f32);
+
+/// The vignette, clear until something hits
+pub fn setup_damage_vignette(mut commands: Commands) {
+    let mut parent = commands
+        .spawn((
+            Node {
+                position_type: PositionType::Absolute,
+                left: Val::Px(0.0),
+                top: Val::Px(0.0),
+                width: Val::Percent(100.0),
+                height: Val::Percent(100.0),
+                ..default()
+            },
+            DamageVignette::default(),
+        ))
+        .id();
+
+    // Rust Concept: Building nested UI in a loop
+    // Each ring is a border just inside the last, fainter towards the middle,
+    // which makes a soft edge without needing a gradient image
+    for alpha in VIGNETTE_RINGS {
+        parent = commands
+            .spawn((
+                Node {
+                    width: Val::Percent(100.0),
+                    height: Val::Percent(100.0),
+                    border: UiRect::all(Val::Px(VIGNETTE_RING_WIDTH)),
+                    ..default()
+                },
+                BorderColor::all(Color::NONE),
+                VignetteRing(alpha),
+                ChildOf(parent),
+            ))
+            .id();
+    }
+}
+
+/// Flash the vignette whenever the hull is hurt and fade it out again
+///
+/// Driven by the ship's HealthChanged rather than DamageEvent, so a hit the shield
+/// soaked up or that came during the grace after a hit doesn't flash
+pub fn update_damage_vignette(
+    time: Res<Time>,
+    mut changes: MessageReader<HealthChanged>,
+    player: Query<(), With<Player>>,
+    mut vignette_query: Query<&mut DamageVignette>,
+    mut ring_query: Query<(&VignetteRing, &mut BorderColor)>,
+) {
+    let Ok(mut vignette) = vignette_query.single_mut() else {
+        return;
+    };
+    let before = vignette.flash;
+    vignette.flash = (vignette.flash - VIGNETTE_FADE * time.delta_secs()).max(0.0);
+
+    // bigger hits glow brighter, grinding against a rock only a little
+    for change in changes
+        .read()
+        .filter(|change| player.contains(change.entity))
+    {
+        let Some(cause) = change.cause else {
+            continue;
+        };
+        let strength = if cause.is_hit() {
+            (-change.change() / 25.0).clamp(0.35, 1.0)
+        } else {
+            0.15
+        };
+        vignette.flash = vignette.flash.max(strength);
+    }
+    if before == 0.0 && vignette.flash == 0.0 {
+        return;
+    }
+
+    for (ring, mut border) in ring_query.iter_mut() {
+        *border = BorderColor::all(Color::srgba(0.9, 0.0, 0.0, ring.0 * vignette.flash));
+    }
+}
+
+/// An arrow beside the ship pointing towards where a hit came from, fading out
+#[derive(Component)]
+pub(crate) struct DamageIndicator {
+    direction: Vec2,
+    timer: Timer,
+}
+
+/// Put up an arrow for every hit that hurt the hull, grinding against a rock doesn't get one
+pub fn spawn_damage_indicators(
+    mut commands: Commands,
+    mut changes: MessageReader<HealthChanged>,
+    player: Query<(), With<Player>>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+) {
+    let hits = changes.read().filter(|change| {
+        player.contains(change.entity) && change.cause.is_some_and(|cause| cause.is_hit())
+    });
+    for hit in hits {
+        // a blast right on top of the ship has no direction
+        let Some(direction) = (hit.from - hit.position).truncate().try_normalize() else {
+            continue;
+        };
+
+        commands.spawn((
+            Name::new("DamageIndicator"),
+            Mesh2d(meshes.add(Triangle2d::new(
+                Vec2::new(0.0, 10.0),
+                Vec2::new(-9.0, -6.0),
+                Vec2::new(9.0, -6.0),
+            ))),
+            MeshMaterial2d(materials.add(Color::srgba(1.0, 0.2, 0.15, 0.9))),
+            indicator_transform(hit.position, direction),
+            DamageIndicator {
+                direction,
+                timer: Timer::from_seconds(INDICATOR_SECS, TimerMode::Once),
+            },
+            DespawnOnExit(AppState::Playing),
+        ));
+    }
+}
+
+/// Keep the arrows with the ship as it moves, fading until they're gone
+pub fn update_damage_indicators(
+    mut commands: Commands,
+    time: Res<Time>,
+    player_query: Query<&Transform, (With<Player>, Without<DamageIndicator>)>,
+    mut indicator_query: Query<(
+        Entity,
+        &mut DamageIndicator,
+        &mut Transform,
+        &MeshMaterial2d<ColorMaterial>,
+    )>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+) {
+    let ship = player_query.single().ok();
+
+    for (entity, mut indicator, mut transform, material) in indicator_query.iter_mut() {
+        indicator.timer.tick(time.delta());
+        let Some(ship) = ship.filter(|_| !indicator.timer.is_finished()) else {
+            commands.entity(entity).despawn();
+            continue;
+        };
+
+        *transform = indicator_transform(ship.translation, indicator.direction);
+        if let Some(material) = materials.get_mut(&material.0) {
+            material
+                .color
+                .set_alpha(0.9 * indicator.timer.fraction_remaining());
+        }
+    }
+}
+
+// just outside the ship on the side the hit came from, pointing outwards
+fn indicator_transform(ship: Vec3, direction: Vec2) -> Transform {
+    Transform::from_translation(ship + (direction * INDICATOR_DISTANCE).extend(1.0))
+        .with_rotation(Quat::from_rotation_z(Vec2::Y.angle_to(direction)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_health_bar_drains_after_a_hit() {
+        let tick = 1.0 / 60.0;
+        let mut bar = HealthBar {
+            shown: 1.0,
+            trailing: 1.0,
+            hold: 0.0,
+        };
+
+        // the bright part drops at once, the lost chunk waits and then drains
+        bar.follow(0.6, tick);
+        assert_eq!(bar.shown, 0.6);
+        assert_eq!(bar.trailing, 1.0);
+        for _ in 0..60 {
+            bar.follow(0.6, tick);
+        }
+        assert!(bar.trailing < 1.0 && bar.trailing >= bar.shown);
+
+        // a repair fills back up smoothly
+        bar.follow(0.8, tick);
+        assert!(bar.shown > 0.6 && bar.shown < 0.8);
+    }
+
+    #[test]
+    fn test_low_health_warns_once() {
+        let change = |previous: f32, new_health: f32| HealthChanged {
+            entity: Entity::PLACEHOLDER,
+            previous,
+            new_health,
+            max_health: 100.0,
+            position: Vec3::ZERO,
+            from: Vec3::ZERO,
+            cause: None,
+        };
+
+        assert!(drops_into_the_red(&change(40.0, 20.0)));
+        assert!(!drops_into_the_red(&change(20.0, 10.0)));
+        assert!(!drops_into_the_red(&change(50.0, 40.0)));
+        // dying gets its own sound
+        assert!(!drops_into_the_red(&change(40.0, 0.0)));
+    }
+}
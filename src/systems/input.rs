@@ -0,0 +1,270 @@
+//! Turning keys and gamepads into ShipControls
+//!
+//! Every input goes through InputBindings, so remapping a key only means editing
+//! the bindings file (bindings.txt next to the game, or local storage on the web).
+
+use crate::resources::{ActionBinding, InputBindings, RunMode, ShipAction, ShipControls};
+use crate::storage;
+use bevy::prelude::*;
+
+const BINDINGS_KEY: &str = "bindings.txt";
+
+/// Keys that can be named in the bindings file
+const BINDABLE_KEYS: [KeyCode; 56] = [
+    KeyCode::KeyA,
+    KeyCode::KeyB,
+    KeyCode::KeyC,
+    KeyCode::KeyD,
+    KeyCode::KeyE,
+    KeyCode::KeyF,
+    KeyCode::KeyG,
+    KeyCode::KeyH,
+    KeyCode::KeyI,
+    KeyCode::KeyJ,
+    KeyCode::KeyK,
+    KeyCode::KeyL,
+    KeyCode::KeyM,
+    KeyCode::KeyN,
+    KeyCode::KeyO,
+    KeyCode::KeyP,
+    KeyCode::KeyQ,
+    KeyCode::KeyR,
+    KeyCode::KeyS,
+    KeyCode::KeyT,
+    KeyCode::KeyU,
+    KeyCode::KeyV,
+    KeyCode::KeyW,
+    KeyCode::KeyX,
+    KeyCode::KeyY,
+    KeyCode::KeyZ,
+    KeyCode::Digit0,
+    KeyCode::Digit1,
+    KeyCode::Digit2,
+    KeyCode::Digit3,
+    KeyCode::Digit4,
+    KeyCode::Digit5,
+    KeyCode::Digit6,
+    KeyCode::Digit7,
+    KeyCode::Digit8,
+    KeyCode::Digit9,
+    KeyCode::ArrowUp,
+    KeyCode::ArrowDown,
+    KeyCode::ArrowLeft,
+    KeyCode::ArrowRight,
+    KeyCode::Space,
+    KeyCode::Enter,
+    KeyCode::Tab,
+    KeyCode::ShiftLeft,
+    KeyCode::ShiftRight,
+    KeyCode::ControlLeft,
+    KeyCode::ControlRight,
+    KeyCode::AltLeft,
+    KeyCode::AltRight,
+    KeyCode::Numpad0,
+    KeyCode::Numpad2,
+    KeyCode::Numpad4,
+    KeyCode::Numpad5,
+    KeyCode::Numpad6,
+    KeyCode::Numpad8,
+    KeyCode::NumpadEnter,
+];
+
+/// Gamepad buttons that can be named in the bindings file
+const BINDABLE_BUTTONS: [GamepadButton; 16] = [
+    GamepadButton::South,
+    GamepadButton::East,
+    GamepadButton::North,
+    GamepadButton::West,
+    GamepadButton::LeftTrigger,
+    GamepadButton::LeftTrigger2,
+    GamepadButton::RightTrigger,
+    GamepadButton::RightTrigger2,
+    GamepadButton::LeftThumb,
+    GamepadButton::RightThumb,
+    GamepadButton::DPadUp,
+    GamepadButton::DPadDown,
+    GamepadButton::DPadLeft,
+    GamepadButton::DPadRight,
+    GamepadButton::Select,
+    GamepadButton::Start,
+];
+
+/// Load the bindings at startup
+/// The first launch writes the defaults out so there's a file to edit
+pub fn load_input_bindings(mut bindings: ResMut<InputBindings>) {
+    match storage::read(BINDINGS_KEY) {
+        Some(contents) => *bindings = decode_bindings(&contents),
+        None => {
+            if let Err(e) = storage::write(BINDINGS_KEY, &encode_bindings(&bindings)) {
+                warn!("Could not save default bindings: {e}");
+            }
+        }
+    }
+}
+
+/// Sample keys and gamepads once per frame, runs in PreUpdate right after Bevy reads input
+///
+/// Thrusters and the trigger follow whatever is held right now. Presses are latched
+/// until a simulation tick uses them, so they're never lost on frames without a tick
+/// and never count twice on frames with several.
+pub fn read_ship_controls(
+    keyboard: Res<ButtonInput<KeyCode>>,
+    gamepads: Query<&Gamepad>,
+    bindings: Res<InputBindings>,
+    run_mode: Res<RunMode>,
+    mut controls: ResMut<ShipControls>,
+) {
+    // a replay is driving the ship
+    if run_mode.is_replay() {
+        return;
+    }
+
+    let strength =
+        |action: ShipAction| action_strength(bindings.binding(action), &keyboard, &gamepads);
+    let turn_left = strength(ShipAction::TurnLeft);
+    let turn_right = strength(ShipAction::TurnRight);
+    let thrust = strength(ShipAction::Thrust);
+
+    // turning left fires the right thruster and vice versa, thrust fires both
+    controls.left_thruster = ShipControls::quantize(turn_right.max(thrust));
+    controls.right_thruster = ShipControls::quantize(turn_left.max(thrust));
+    controls.reverse = ShipControls::quantize(strength(ShipAction::Reverse));
+    controls.fire = strength(ShipAction::Fire) > 0.0;
+
+    let just_pressed =
+        |action: ShipAction| action_just_pressed(bindings.binding(action), &keyboard, &gamepads);
+    controls.fire_pressed |= just_pressed(ShipAction::Fire);
+    controls.switch_weapon |= just_pressed(ShipAction::SwitchWeapon);
+}
+
+/// Last system of every simulation tick: the latched presses have been used
+pub fn clear_latched_input(mut controls: ResMut<ShipControls>) {
+    controls.fire_pressed = false;
+    controls.switch_weapon = false;
+}
+
+/// How hard an action is pushed, the strongest of all its inputs
+/// Keys and digital buttons are all or nothing, triggers report how far they're squeezed
+fn action_strength(
+    binding: &ActionBinding,
+    keyboard: &ButtonInput<KeyCode>,
+    gamepads: &Query<&Gamepad>,
+) -> f32 {
+    if keyboard.any_pressed(binding.keys.iter().copied()) {
+        return 1.0;
+    }
+
+    gamepads
+        .iter()
+        .flat_map(|gamepad| {
+            binding.buttons.iter().map(move |button| {
+                if gamepad.pressed(*button) {
+                    1.0
+                } else {
+                    gamepad.get(*button).unwrap_or(0.0)
+                }
+            })
+        })
+        .fold(0.0, f32::max)
+}
+
+fn action_just_pressed(
+    binding: &ActionBinding,
+    keyboard: &ButtonInput<KeyCode>,
+    gamepads: &Query<&Gamepad>,
+) -> bool {
+    keyboard.any_just_pressed(binding.keys.iter().copied())
+        || gamepads.iter().any(|gamepad| {
+            binding
+                .buttons
+                .iter()
+                .any(|button| gamepad.just_pressed(*button))
+        })
+}
+
+// one line per action: its name, then every key and button bound to it
+fn encode_bindings(bindings: &InputBindings) -> String {
+    let mut contents = String::from("# <action> followed by the keys and gamepad buttons for it\n");
+    for action in ShipAction::ALL {
+        let binding = bindings.binding(action);
+        let inputs: Vec<String> = binding
+            .keys
+            .iter()
+            .map(|key| format!("{key:?}"))
+            .chain(binding.buttons.iter().map(|button| format!("{button:?}")))
+            .collect();
+        contents.push_str(&format!("{} {}\n", action.config_name(), inputs.join(" ")));
+    }
+    contents
+}
+
+// actions missing from the file keep their defaults, unknown names are skipped
+fn decode_bindings(contents: &str) -> InputBindings {
+    let mut bindings = InputBindings::default();
+
+    for line in contents.lines().map(str::trim) {
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        let mut words = line.split_whitespace();
+        let name = words.next().unwrap_or_default();
+        let Some(action) = ShipAction::ALL
+            .into_iter()
+            .find(|action| action.config_name() == name)
+        else {
+            warn!("Unknown action in bindings: {name}");
+            continue;
+        };
+
+        let mut binding = ActionBinding::default();
+        for word in words {
+            if let Some(key) = BINDABLE_KEYS.iter().find(|key| format!("{key:?}") == word) {
+                binding.keys.push(*key);
+            } else if let Some(button) = BINDABLE_BUTTONS
+                .iter()
+                .find(|button| format!("{button:?}") == word)
+            {
+                binding.buttons.push(*button);
+            } else {
+                warn!("Unknown key or button in bindings: {word}");
+            }
+        }
+        bindings.actions.insert(action, binding);
+    }
+
+    bindings
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bindings_round_trip() {
+        let mut bindings = InputBindings::default();
+        bindings.actions.insert(
+            ShipAction::Fire,
+            ActionBinding {
+                keys: vec![KeyCode::KeyJ, KeyCode::ControlLeft],
+                buttons: vec![GamepadButton::East],
+            },
+        );
+
+        assert_eq!(decode_bindings(&encode_bindings(&bindings)), bindings);
+    }
+
+    #[test]
+    fn test_bindings_file_is_forgiving() {
+        let bindings = decode_bindings("# comment\n\nfire KeyF Banana North\nwarp KeyX\n");
+
+        let fire = bindings.binding(ShipAction::Fire);
+        assert_eq!(fire.keys, vec![KeyCode::KeyF]);
+        assert_eq!(fire.buttons, vec![GamepadButton::North]);
+        // untouched actions keep the defaults
+        assert_eq!(
+            bindings.binding(ShipAction::Thrust),
+            InputBindings::default().binding(ShipAction::Thrust)
+        );
+    }
+}
@@ -0,0 +1,596 @@
+//! The campaign: scripted levels loaded from `assets/levels/campaign.levels.ron`
+//!
+//! Each level sends in timed groups of asteroids in formation and ends when its
+//! clear condition is met, followed by an intermission with a summary of how it
+//! went. After the last level the run carries on in endless mode, where the
+//! spawn timer and the difficulty curve take over.
+
+use crate::components::{Asteroid, AsteroidMaterial, Health, Player};
+use crate::events::{AsteroidDestroyed, HealEvent};
+use crate::resources::*;
+use crate::systems::asteroid::AsteroidSpawner;
+use crate::systems::config::RonAsset;
+use avian2d::prelude::*;
+use bevy::prelude::*;
+use rand::Rng;
+use serde::Deserialize;
+use std::f32::consts::TAU;
+
+pub const CAMPAIGN_PATH: &str = "levels/campaign.levels.ron";
+
+/// The intermission moves on by itself after this long
+const INTERMISSION_SECS: f32 = 8.0;
+/// Fire skips the intermission, but not straight away so a held trigger doesn't
+const INTERMISSION_MIN_SECS: f32 = 1.5;
+
+/// Every level in the order they're played
+#[derive(Asset, TypePath, Deserialize, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct Campaign {
+    pub levels: Vec<Level>,
+    #[serde(skip)]
+    pub fingerprint: u64, // of the file it was read from
+}
+
+#[derive(Deserialize, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct Level {
+    pub name: String,
+    pub clear: ClearCondition,
+    #[serde(default)]
+    pub groups: Vec<SpawnGroup>,
+}
+
+impl Level {
+    /// When the last group comes in, in seconds from the start of the level
+    fn last_spawn(&self) -> f32 {
+        self.groups
+            .iter()
+            .flat_map(SpawnGroup::spawn_times)
+            .fold(0.0, f32::max)
+    }
+}
+
+/// What it takes to finish a level
+#[derive(Deserialize, Clone, Copy, Debug, PartialEq)]
+pub enum ClearCondition {
+    Survive(f32), // seconds
+    DestroyAll,   // every group has come in and no rock is left
+    Score(u32),   // points earned during the level
+}
+
+/// How the rocks in a group are arranged
+#[derive(Deserialize, Clone, Copy, Debug, PartialEq, Default)]
+pub enum Formation {
+    #[default]
+    Single,
+    /// Side by side
+    Line {
+        count: u32,
+        spacing: f32,
+    },
+    /// One behind the other
+    Column {
+        count: u32,
+        spacing: f32,
+    },
+    /// A V with its point leading
+    Vee {
+        count: u32,
+        spacing: f32,
+    },
+    Ring {
+        count: u32,
+        radius: f32,
+    },
+}
+
+impl Formation {
+    /// Where each rock sits relative to the group's lead, for a group heading straight down
+    pub fn offsets(&self) -> Vec<Vec2> {
+        match *self {
+            Formation::Single => vec![Vec2::ZERO],
+            Formation::Line { count, spacing } => (0..count)
+                .map(|i| Vec2::new((i as f32 - (count - 1) as f32 / 2.0) * spacing, 0.0))
+                .collect(),
+            Formation::Column { count, spacing } => (0..count)
+                .map(|i| Vec2::new(0.0, i as f32 * spacing))
+                .collect(),
+            // the point first, then a rock either side on each row further back
+            Formation::Vee { count, spacing } => (0..count)
+                .map(|i| {
+                    let row = i.div_ceil(2) as f32;
+                    let side = if i % 2 == 1 { -1.0 } else { 1.0 };
+                    Vec2::new(side * row * spacing, row * spacing)
+                })
+                .collect(),
+            Formation::Ring { count, radius } => (0..count)
+                .map(|i| Vec2::from_angle(i as f32 / count as f32 * TAU) * radius)
+                .collect(),
+        }
+    }
+
+    fn count(&self) -> u32 {
+        match *self {
+            Formation::Single => 1,
+            Formation::Line { count, .. }
+            | Formation::Column { count, .. }
+            | Formation::Vee { count, .. }
+            | Formation::Ring { count, .. } => count,
+        }
+    }
+}
+
+/// A group of asteroids that comes in together, optionally more than once
+#[derive(Deserialize, Clone, Debug)]
+#[serde(default, deny_unknown_fields)]
+pub struct SpawnGroup {
+    pub at: f32, // seconds into the level
+    pub formation: Formation,
+    pub edge: ScreenEdge,
+    pub along: Option<f32>, // -1 to 1 along the edge (left to right, bottom to top), random if left out
+    pub aim: bool,          // head for the ship instead of straight in
+    pub heading: f32,       // degrees turned anticlockwise, so positive drifts right from the top
+    pub radius: (f32, f32),
+    pub speed: (f32, f32),
+    pub material: Option<AsteroidMaterial>, // picked from the material weights if left out
+    pub repeat: u32,                        // extra times the group comes in
+    pub every: f32,                         // seconds between repeats
+}
+
+impl Default for SpawnGroup {
+    fn default() -> Self {
+        Self {
+            at: 0.0,
+            formation: Formation::Single,
+            edge: ScreenEdge::Top,
+            along: None,
+            aim: false,
+            heading: 0.0,
+            radius: (20.0, 40.0),
+            speed: (100.0, 200.0),
+            material: None,
+            repeat: 0,
+            every: 2.0,
+        }
+    }
+}
+
+impl SpawnGroup {
+    fn spawn_times(&self) -> impl Iterator<Item = f32> + '_ {
+        (0..=self.repeat).map(move |i| self.at + i as f32 * self.every)
+    }
+}
+
+impl RonAsset for Campaign {
+    const DESCRIPTION: &'static str = "campaign";
+    const EXTENSIONS: &'static [&'static str] = &["levels.ron"];
+
+    fn set_fingerprint(&mut self, fingerprint: u64) {
+        self.fingerprint = fingerprint;
+    }
+
+    fn validate(&self) -> Result<(), String> {
+        let mut problems = Vec::new();
+        if self.levels.is_empty() {
+            problems.push("a campaign needs at least one level".to_string());
+        }
+
+        for level in &self.levels {
+            let mut check = |ok: bool, problem: &str| {
+                if !ok {
+                    problems.push(format!("{}: {problem}", level.name));
+                }
+            };
+
+            match level.clear {
+                ClearCondition::Survive(secs) => check(secs > 0.0, "Survive needs a time above 0"),
+                ClearCondition::DestroyAll => check(
+                    !level.groups.is_empty(),
+                    "DestroyAll needs something to destroy",
+                ),
+                ClearCondition::Score(_) => {}
+            }
+
+            for group in &level.groups {
+                check(
+                    group.at >= 0.0,
+                    "groups can't come in before the level starts",
+                );
+                check(
+                    group.formation.count() > 0,
+                    "formations need at least one rock",
+                );
+                check(
+                    group.radius.0 > 0.0 && group.radius.0 <= group.radius.1,
+                    "radius must be a (min, max) pair above 0",
+                );
+                check(
+                    group.speed.0 > 0.0 && group.speed.0 <= group.speed.1,
+                    "speed must be a (min, max) pair above 0",
+                );
+                check(
+                    group
+                        .along
+                        .is_none_or(|along| (-1.0..=1.0).contains(&along)),
+                    "along must be between -1 and 1",
+                );
+                // any steeper and rocks drift off the side before they come in
+                check(
+                    group.heading.abs() <= 60.0,
+                    "heading must be within 60 degrees of straight in",
+                );
+                check(
+                    group.repeat == 0 || group.every > 0.0,
+                    "repeating groups need an every above 0",
+                );
+            }
+        }
+
+        if problems.is_empty() {
+            Ok(())
+        } else {
+            Err(problems.join("; "))
+        }
+    }
+}
+
+/// Keeps the campaign loaded (and watched) for the whole session
+#[derive(Resource)]
+pub struct CampaignHandle(pub Handle<Campaign>);
+
+/// Only spawn on a timer once the campaign has run out of levels
+pub fn endless_mode(progress: Res<LevelProgress>) -> bool {
+    progress.phase == LevelPhase::Endless
+}
+
+/// Play the current level: send in its groups on time and check whether it's cleared
+/// Runs in the simulation, so a level plays out the same way in a replay
+#[allow(clippy::too_many_arguments)]
+pub fn run_level(
+    mut spawner: AsteroidSpawner,
+    mut progress: ResMut<LevelProgress>,
+    mut destroyed: MessageReader<AsteroidDestroyed>,
+    campaign: Res<CampaignHandle>,
+    campaigns: Res<Assets<Campaign>>,
+    config: Res<AsteroidSpawnConfig>,
+    game_data: Res<GameData>,
+    controls: Res<ShipControls>,
+    director: Res<Director>,
+    damage_config: Res<DamageConfig>,
+    mut heals: MessageWriter<HealEvent>,
+    asteroids: Query<(), With<Asteroid>>,
+    player: Query<(Entity, &Health, &Transform, &LinearVelocity), With<Player>>,
+    time: Res<Time>,
+) {
+    progress.asteroids_destroyed += destroyed.read().count() as u32;
+    let dt = time.delta_secs();
+
+    // a campaign that failed to load goes straight to endless mode
+    let Some(campaign) = campaigns.get(&campaign.0) else {
+        progress.phase = LevelPhase::Endless;
+        return;
+    };
+
+    match progress.phase {
+        LevelPhase::Endless => {}
+        LevelPhase::Intermission => {
+            progress.elapsed += dt;
+            let skipped = controls.fire_pressed && progress.elapsed > INTERMISSION_MIN_SECS;
+            if !skipped && progress.elapsed < INTERMISSION_SECS {
+                return;
+            }
+
+            progress.level += 1;
+            progress.elapsed = 0.0;
+            progress.asteroids_destroyed = 0;
+            progress.score_at_start = game_data.score;
+            progress.summary = None;
+            progress.phase = if progress.level < campaign.levels.len() {
+                LevelPhase::Playing
+            } else {
+                info!("Campaign complete, carrying on in endless mode");
+                LevelPhase::Endless
+            };
+        }
+        LevelPhase::Playing => {
+            // the file may have been edited down to fewer levels mid-run
+            let Some(level) = campaign.levels.get(progress.level) else {
+                progress.phase = LevelPhase::Endless;
+                return;
+            };
+
+            // Rust Concept: Half-open time windows
+            // Each tick covers [previous, elapsed), so a spawn time can't land in two ticks
+            let previous = progress.elapsed;
+            progress.elapsed += dt;
+            let target = player
+                .single()
+                .ok()
+                .map(|(_, _, transform, velocity)| (transform.translation.truncate(), velocity.0));
+            for group in &level.groups {
+                for _ in group
+                    .spawn_times()
+                    .filter(|at| (previous..progress.elapsed).contains(at))
+                {
+                    spawn_group(&mut spawner, group, &config, target, director.speed_scale);
+                }
+            }
+
+            let cleared = match level.clear {
+                ClearCondition::Survive(secs) => progress.elapsed >= secs,
+                // groups spawned this tick aren't in the query yet
+                ClearCondition::DestroyAll => level.last_spawn() < previous && asteroids.is_empty(),
+                ClearCondition::Score(points) => {
+                    game_data.score.saturating_sub(progress.score_at_start) >= points
+                }
+            };
+            if !cleared {
+                return;
+            }
+
+            info!("Cleared level {}: {}", progress.level + 1, level.name);
+            progress.summary = Some(LevelSummary {
+                name: level.name.clone(),
+                time: progress.elapsed,
+                asteroids_destroyed: progress.asteroids_destroyed,
+                score: game_data.score.saturating_sub(progress.score_at_start),
+                hull: player
+                    .single()
+                    .map_or(0.0, |(_, health, ..)| health.current() / health.max()),
+            });
+            // a breather to patch the hull before the next one
+            if let Ok((entity, ..)) = player.single() {
+                heals.write(HealEvent {
+                    player: entity,
+                    amount: damage_config.repair_per_level,
+                });
+            }
+            progress.phase = LevelPhase::Intermission;
+            progress.elapsed = 0.0;
+        }
+    }
+}
+
+// Send in one group, lined up just outside its edge of the screen
+// The director can't change a level's script, only how fast it comes at the player
+fn spawn_group(
+    spawner: &mut AsteroidSpawner,
+    group: &SpawnGroup,
+    config: &AsteroidSpawnConfig,
+    target: Option<(Vec2, Vec2)>, // the ship's position and velocity
+    speed_scale: f32,
+) {
+    let rng = &mut spawner.game_rng.spawning;
+    let along = group.along.unwrap_or_else(|| rng.random_range(-1.0..=1.0));
+    let start = config.edge_point(group.edge, along);
+    let speed = rng.random_range(group.speed.0..=group.speed.1);
+
+    // straight in from the edge or at the ship, then turned by the heading
+    let inward = group.edge.inward();
+    let aim = target
+        .filter(|_| group.aim)
+        .and_then(|(position, velocity)| config.aim(start, speed, position, velocity));
+    let direction = Vec2::from_angle(group.heading.to_radians()).rotate(aim.unwrap_or(inward));
+    // the whole group moves together
+    let velocity = direction * speed * speed_scale;
+
+    // turn the formation to face where it's heading, then pull it back so no rock starts on screen
+    let facing = Vec2::from_angle(Vec2::NEG_Y.angle_to(direction));
+    let offsets: Vec<Vec2> = group
+        .formation
+        .offsets()
+        .into_iter()
+        .map(|offset| facing.rotate(offset))
+        .collect();
+    let furthest_in = offsets
+        .iter()
+        .map(|offset| offset.dot(inward))
+        .fold(0.0, f32::max);
+    let lead = start - inward * furthest_in;
+
+    for offset in offsets {
+        let radius = spawner
+            .game_rng
+            .spawning
+            .random_range(group.radius.0..=group.radius.1);
+        spawner.spawn(
+            (lead + offset).extend(0.0),
+            velocity,
+            radius,
+            group.material,
+        );
+    }
+}
+
+/// Marker component for the level UI
+#[derive(Component)]
+pub(crate) struct LevelDisplay;
+
+/// The level name and objective, across the top of the screen
+pub fn setup_level_display(mut commands: Commands) {
+    commands
+        .spawn(Node {
+            position_type: PositionType::Absolute,
+            top: Val::Px(14.0),
+            width: Val::Percent(100.0),
+            justify_content: JustifyContent::Center,
+            ..default()
+        })
+        .with_children(|parent| {
+            parent.spawn((
+                Text::new(""),
+                TextFont {
+                    font_size: 20.0,
+                    ..default()
+                },
+                TextColor(Color::srgb(0.8, 0.8, 0.9)),
+                LevelDisplay,
+            ));
+        });
+}
+
+pub fn update_level_display(
+    progress: Res<LevelProgress>,
+    campaign: Res<CampaignHandle>,
+    campaigns: Res<Assets<Campaign>>,
+    game_data: Res<GameData>,
+    mut text_query: Query<&mut Text, With<LevelDisplay>>,
+) {
+    let Ok(mut text) = text_query.single_mut() else {
+        return;
+    };
+
+    let level = campaigns
+        .get(&campaign.0)
+        .and_then(|campaign| campaign.levels.get(progress.level));
+    *text = match (progress.phase, level) {
+        (LevelPhase::Playing, Some(level)) => {
+            let objective = match level.clear {
+                ClearCondition::Survive(secs) => {
+                    format!("survive {:.0}s", (secs - progress.elapsed).max(0.0).ceil())
+                }
+                ClearCondition::DestroyAll => "destroy them all".to_string(),
+                ClearCondition::Score(points) => format!(
+                    "score {} / {points}",
+                    game_data.score.saturating_sub(progress.score_at_start)
+                ),
+            };
+            Text::new(format!(
+                "Level {}: {} - {objective}",
+                progress.level + 1,
+                level.name
+            ))
+        }
+        (LevelPhase::Endless, _) => Text::new("Endless"),
+        _ => Text::new(""),
+    };
+}
+
+/// Marker component for the intermission screen
+#[derive(Component)]
+pub(crate) struct IntermissionOverlay;
+
+/// Show the level summary while the intermission lasts
+pub fn update_intermission_overlay(
+    mut commands: Commands,
+    progress: Res<LevelProgress>,
+    overlay: Query<Entity, With<IntermissionOverlay>>,
+) {
+    if !progress.is_changed() {
+        return;
+    }
+
+    let showing = !overlay.is_empty();
+    let summary = progress
+        .summary
+        .as_ref()
+        .filter(|_| progress.phase == LevelPhase::Intermission);
+
+    match (summary, showing) {
+        (Some(summary), false) => {
+            spawn_intermission_overlay(&mut commands, progress.level, summary)
+        }
+        (None, true) => {
+            for entity in &overlay {
+                commands.entity(entity).despawn();
+            }
+        }
+        _ => {}
+    }
+}
+
+fn spawn_intermission_overlay(commands: &mut Commands, level: usize, summary: &LevelSummary) {
+    let lines = [
+        format!("Time: {:.1}s", summary.time),
+        format!("Asteroids destroyed: {}", summary.asteroids_destroyed),
+        format!("Score earned: {}", summary.score),
+        format!("Hull: {:.0}%", summary.hull * 100.0),
+    ];
+
+    commands
+        .spawn((
+            Node {
+                width: Val::Percent(100.0),
+                height: Val::Percent(100.0),
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                flex_direction: FlexDirection::Column,
+                row_gap: Val::Px(8.0),
+                ..default()
+            },
+            // the game keeps going behind it, only dim it a little
+            BackgroundColor(Color::srgba(0.0, 0.0, 0.0, 0.35)),
+            IntermissionOverlay,
+            DespawnOnExit(AppState::Playing),
+        ))
+        .with_children(|parent| {
+            parent.spawn((
+                Text::new(format!("Level {} cleared: {}", level + 1, summary.name)),
+                TextFont {
+                    font_size: 40.0,
+                    ..default()
+                },
+                TextColor(Color::WHITE),
+            ));
+
+            for line in lines {
+                parent.spawn((
+                    Text::new(line),
+                    TextFont {
+                        font_size: 24.0,
+                        ..default()
+                    },
+                    TextColor(Color::srgb(0.8, 0.8, 0.9)),
+                ));
+            }
+
+            parent.spawn((
+                Text::new("Fire to continue"),
+                TextFont {
+                    font_size: 20.0,
+                    ..default()
+                },
+                TextColor(Color::srgb(0.6, 0.6, 0.7)),
+            ));
+        });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_shipped_campaign_is_valid() {
+        let contents = include_str!("../../assets/levels/campaign.levels.ron");
+        if let Err(e) = Campaign::parse(contents) {
+            panic!("{e}");
+        }
+    }
+
+    #[test]
+    fn test_formations() {
+        let vee = Formation::Vee {
+            count: 5,
+            spacing: 10.0,
+        };
+        assert_eq!(
+            vee.offsets(),
+            vec![
+                Vec2::ZERO,
+                Vec2::new(-10.0, 10.0),
+                Vec2::new(10.0, 10.0),
+                Vec2::new(-20.0, 20.0),
+                Vec2::new(20.0, 20.0),
+            ]
+        );
+
+        // a line is centred on the lead
+        let line = Formation::Line {
+            count: 3,
+            spacing: 50.0,
+        };
+        let total: Vec2 = line.offsets().into_iter().sum();
+        assert_eq!(total, Vec2::ZERO);
+    }
+}
@@ -1,7 +1,11 @@
 use bevy::prelude::*;
 use crate::resources::{AudioAssets, AppState};
+use crate::systems::config::{GAME_CONFIG_PATH, GameConfigHandle};
+use crate::systems::level::{CAMPAIGN_PATH, CampaignHandle};
 
 pub fn setup_loading(mut commands: Commands, asset_server: Res<AssetServer>) {
+    commands.insert_resource(GameConfigHandle(asset_server.load(GAME_CONFIG_PATH)));
+    commands.insert_resource(CampaignHandle(asset_server.load(CAMPAIGN_PATH)));
     commands.insert_resource(
         AudioAssets {
             thruster: asset_server.load("audio/kenney_sci-fi-sounds/Audio/thrusterFire_004.ogg"),
@@ -11,6 +15,9 @@ pub fn setup_loading(mut commands: Commands, asset_server: Res<AssetServer>) {
             game_start: asset_server.load("audio/kenney_sci-fi-sounds/Audio/toggle_003.ogg"),
             game_over: asset_server.load("audio/kenney_sci-fi-sounds/Audio/explosionCrunch_002.ogg"),
             menu_boop: asset_server.load("audio/kenney_sci-fi-sounds/Audio/glitch_004.ogg"),
+            shield_up: asset_server.load("audio/kenney_sci-fi-sounds/Audio/forceField_000.ogg"),
+            shield_hit: asset_server.load("audio/kenney_sci-fi-sounds/Audio/forceField_002.ogg"),
+            shield_down: asset_server.load("audio/kenney_sci-fi-sounds/Audio/forceField_004.ogg"),
         }
     );
 }
@@ -18,11 +25,19 @@ pub fn setup_loading(mut commands: Commands, asset_server: Res<AssetServer>) {
 pub fn check_loading(
     asset_server: Res<AssetServer>,
     mut next_state: ResMut<NextState<AppState>>,
-    audio_assets: ResMut<AudioAssets>) {
+    audio_assets: ResMut<AudioAssets>,
+    config: Res<GameConfigHandle>,
+    campaign: Res<CampaignHandle>) {
 
     let all_loaded = audio_assets.handles().iter().all(|handle| {asset_server.is_loaded_with_dependencies(handle)});
+    // a broken config file shouldn't lock the game on the loading screen, the defaults still work
+    let config_done = asset_server.is_loaded_with_dependencies(&config.0)
+        || asset_server.load_state(&config.0).is_failed();
+    // without a campaign the run starts straight in endless mode
+    let campaign_done = asset_server.is_loaded_with_dependencies(&campaign.0)
+        || asset_server.load_state(&campaign.0).is_failed();
 
-    if all_loaded {
+    if all_loaded && config_done && campaign_done {
         next_state.set(AppState::Menu)
     } 
 
@@ -1,9 +1,34 @@
 use crate::events::*;
-use crate::resources::{AppState, GameData};
+use crate::resources::{
+    AppState, HighScores, LoadedReplay, MAX_SEED_DIGITS, PlayerSettings, RunMode, SeedSettings,
+};
+use crate::systems::config::LoadedTuning;
+use crate::systems::high_score::high_score_table_text;
+use bevy::input::ButtonState;
+use bevy::input::keyboard::KeyboardInput;
 use bevy::prelude::*;
 
+/// Marker component for the seed line on the menu
+#[derive(Component)]
+pub(crate) struct SeedText;
+
 // This spawns the Menu UI
-pub fn setup_menu(mut commands: Commands) {
+pub fn setup_menu(
+    mut commands: Commands,
+    high_scores: Res<HighScores>,
+    seed_settings: Res<SeedSettings>,
+    loaded_replay: Res<LoadedReplay>,
+    tuning: LoadedTuning,
+) {
+    let title = match &loaded_replay.0 {
+        Some(replay) if replay.tuning == tuning.fingerprint() => {
+            "Begin Your Game!\nPress <Enter>\n<R> to watch the last replay"
+        }
+        // it would play out a different game than the one recorded
+        Some(_) => "Begin Your Game!\nPress <Enter>\n(the last replay used other tuning)",
+        None => "Begin Your Game!\nPress <Enter>",
+    };
+
     // spawn a root node that covers the screen, flexbox container
     commands
         .spawn((
@@ -13,19 +38,37 @@ pub fn setup_menu(mut commands: Commands) {
                 justify_content: JustifyContent::Center,
                 align_items: AlignItems::Center,
                 flex_direction: FlexDirection::Column,
+                row_gap: Val::Px(30.0),
                 ..default()
             },
             DespawnOnExit(AppState::Menu),
         ))
         .with_children(|parent| {
             parent.spawn((
-                Text::new("Begin Your Game!\nPress <Enter>"),
+                Text::new(title),
                 TextFont {
                     font_size: 48.0,
                     ..default()
                 },
                 TextColor(Color::WHITE),
             ));
+            parent.spawn((
+                Text::new(seed_text(&seed_settings)),
+                TextFont {
+                    font_size: 18.0,
+                    ..default()
+                },
+                TextColor(Color::srgb(0.8, 0.8, 0.9)),
+                SeedText,
+            ));
+            parent.spawn((
+                Text::new(high_score_table_text(&high_scores)),
+                TextFont {
+                    font_size: 20.0,
+                    ..default()
+                },
+                TextColor(Color::srgb(0.8, 0.8, 0.9)),
+            ));
         });
     // Add text children for title and instructions
     // Make sure to include StateScoped(AppState:Menu) in root
@@ -34,15 +77,84 @@ pub fn setup_menu(mut commands: Commands) {
 // menu input, runs every frame while in menu state
 pub fn handle_menu_input(
     keyboard: Res<ButtonInput<KeyCode>>,
+    touches: Res<Touches>,
     mut next_state: ResMut<NextState<AppState>>,
     mut message: MessageWriter<PlaySoundEvent>,
-    mut game_data: ResMut<GameData>,
+    mut run_mode: ResMut<RunMode>,
+    mut player_settings: ResMut<PlayerSettings>,
+    loaded_replay: Res<LoadedReplay>,
+    tuning: LoadedTuning,
 ) {
-    // Check for Enter Key, transition to playing
-    if keyboard.just_pressed(KeyCode::Enter) {
-        message.write(PlaySoundEvent::MenuBoop); // this will move when i have multiple ships to select
-        message.write(PlaySoundEvent::GameStart); // this belongs here
-        game_data.score = 0;
+    // Check for Enter Key (or a tap), transition to ship selection
+    if keyboard.just_pressed(KeyCode::Enter) || touches.any_just_pressed() {
+        message.write(PlaySoundEvent::MenuBoop);
+        *run_mode = RunMode::Live;
+        next_state.set(AppState::ShipSelect);
+    } else if keyboard.just_pressed(KeyCode::KeyR)
+        && let Some(replay) = &loaded_replay.0
+    {
+        if replay.tuning != tuning.fingerprint() {
+            warn!(
+                "Not playing the replay, the config or campaign has changed since it was recorded"
+            );
+            return;
+        }
+        // straight into the run, flying the recorded ship
+        message.write(PlaySoundEvent::GameStart);
+        player_settings.ship_type = replay.ship_type;
+        *run_mode = RunMode::Replay {
+            replay: replay.clone(),
+            tick: 0,
+        };
         next_state.set(AppState::Playing);
     }
 }
+
+/// Type digits on the menu to fix the seed, Backspace to edit, empty means random
+pub fn handle_seed_entry(
+    mut key_events: MessageReader<KeyboardInput>,
+    mut seed_settings: ResMut<SeedSettings>,
+    mut text_query: Query<&mut Text, With<SeedText>>,
+) {
+    let mut digits = seed_settings
+        .fixed_seed
+        .map(|seed| seed.to_string())
+        .unwrap_or_default();
+    let before = digits.clone();
+
+    for event in key_events.read() {
+        if event.state != ButtonState::Pressed {
+            continue;
+        }
+        if event.key_code == KeyCode::Backspace {
+            digits.pop();
+            continue;
+        }
+        let Some(text) = &event.text else {
+            continue;
+        };
+        for c in text.chars().filter(char::is_ascii_digit) {
+            if digits.len() < MAX_SEED_DIGITS {
+                digits.push(c);
+            }
+        }
+    }
+
+    if digits == before {
+        return;
+    }
+
+    // Rust Concept: Result to Option
+    // an empty string fails to parse, which is exactly "no fixed seed"
+    seed_settings.fixed_seed = digits.parse().ok();
+    if let Ok(mut text) = text_query.single_mut() {
+        *text = Text::new(seed_text(&seed_settings));
+    }
+}
+
+fn seed_text(seed_settings: &SeedSettings) -> String {
+    match seed_settings.fixed_seed {
+        Some(seed) => format!("Seed: {seed}"),
+        None => "Seed: random (type a number to pick one)".to_string(),
+    }
+}
@@ -5,12 +5,24 @@ pub mod asteroid;
 pub mod background;
 pub mod camera;
 pub mod collision;
+pub mod config;
+pub mod director;
 pub mod game_over;
 pub mod health;
+pub mod high_score;
+pub mod hud;
+pub mod input;
+pub mod level;
 pub mod loading;
 pub mod menu;
+pub mod pause;
 pub mod player;
+pub mod power_up;
 pub mod projectile;
+pub mod replay;
+pub mod session;
+pub mod ship_select;
+pub mod touch;
 
 // Re-export all public functions for convenience
 // Rust Concept: Selective re-exports
@@ -19,9 +31,21 @@ pub use asteroid::*;
 pub use background::*;
 pub use camera::*;
 pub use collision::*;
+pub use config::*;
+pub use director::*;
 pub use game_over::*;
 pub use health::*;
+pub use high_score::*;
+pub use hud::*;
+pub use input::*;
+pub use level::*;
 pub use loading::*;
 pub use menu::*;
+pub use pause::*;
 pub use player::*;
+pub use power_up::*;
 pub use projectile::*;
+pub use replay::*;
+pub use session::*;
+pub use ship_select::*;
+pub use touch::*;
@@ -0,0 +1,180 @@
+//! Pausing a run
+//!
+//! Pausing stops the virtual clock, which freezes everything driven by Time:
+//! Lifetime timers, the SpawnTimer and the particle effects. Physics time is
+//! paused as well so avian doesn't step, and the looping thruster sound is muted.
+
+use crate::components::ThrusterAudio;
+use crate::events::*;
+use crate::resources::{AppState, PlayState};
+use avian2d::prelude::*;
+use bevy::prelude::*;
+use bevy::window::WindowFocused;
+
+const BUTTON_COLOR: Color = Color::srgb(0.15, 0.15, 0.25);
+const BUTTON_HOVER_COLOR: Color = Color::srgb(0.25, 0.25, 0.4);
+
+/// Buttons on the pause overlay
+#[derive(Component, Clone, Copy)]
+pub(crate) enum PauseButton {
+    Resume,
+    Restart,
+    Quit,
+}
+
+impl PauseButton {
+    fn label(&self) -> &'static str {
+        match self {
+            Self::Resume => "Resume <Esc>",
+            Self::Restart => "Restart <R>",
+            Self::Quit => "Quit to Menu <Q>",
+        }
+    }
+}
+
+/// Escape or P toggles between running and paused
+pub fn toggle_pause(
+    keyboard: Res<ButtonInput<KeyCode>>,
+    state: Res<State<PlayState>>,
+    mut next_state: ResMut<NextState<PlayState>>,
+) {
+    if !(keyboard.just_pressed(KeyCode::Escape) || keyboard.just_pressed(KeyCode::KeyP)) {
+        return;
+    }
+
+    next_state.set(match state.get() {
+        PlayState::Running => PlayState::Paused,
+        PlayState::Paused => PlayState::Running,
+    });
+}
+
+/// Pause when the window loses focus (alt-tab, or clicking off the wasm canvas)
+pub fn pause_on_focus_lost(
+    mut events: MessageReader<WindowFocused>,
+    mut next_state: ResMut<NextState<PlayState>>,
+) {
+    if events.read().any(|event| !event.focused) {
+        next_state.set(PlayState::Paused);
+    }
+}
+
+/// Freeze the clocks and silence the thrusters
+pub fn enter_pause(
+    mut virtual_time: ResMut<Time<Virtual>>,
+    mut physics_time: ResMut<Time<Physics>>,
+    thruster_audio: Query<&AudioSink, With<ThrusterAudio>>,
+) {
+    virtual_time.pause();
+    physics_time.pause();
+    for sink in &thruster_audio {
+        sink.pause();
+    }
+}
+
+/// Start the clocks again
+/// Also runs when the run ends from the pause menu, so GameOver and Menu never see frozen time
+pub fn exit_pause(
+    mut virtual_time: ResMut<Time<Virtual>>,
+    mut physics_time: ResMut<Time<Physics>>,
+) {
+    virtual_time.unpause();
+    physics_time.unpause();
+    // update_thruster_audio picks the thruster sound back up if a key is still held
+}
+
+// This spawns the pause overlay, despawned as soon as we stop being paused
+pub fn setup_pause_menu(mut commands: Commands) {
+    commands
+        .spawn((
+            Node {
+                width: Val::Percent(100.0),
+                height: Val::Percent(100.0),
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                flex_direction: FlexDirection::Column,
+                row_gap: Val::Px(12.0),
+                ..default()
+            },
+            // dim the frozen game behind the menu
+            BackgroundColor(Color::srgba(0.0, 0.0, 0.0, 0.6)),
+            DespawnOnExit(PlayState::Paused),
+        ))
+        .with_children(|parent| {
+            parent.spawn((
+                Text::new("Paused"),
+                TextFont {
+                    font_size: 48.0,
+                    ..default()
+                },
+                TextColor(Color::WHITE),
+            ));
+
+            for button in [PauseButton::Resume, PauseButton::Restart, PauseButton::Quit] {
+                parent
+                    .spawn((
+                        Button,
+                        Node {
+                            width: Val::Px(260.0),
+                            padding: UiRect::all(Val::Px(10.0)),
+                            justify_content: JustifyContent::Center,
+                            ..default()
+                        },
+                        BackgroundColor(BUTTON_COLOR),
+                        button,
+                    ))
+                    .with_children(|b| {
+                        b.spawn((
+                            Text::new(button.label()),
+                            TextFont {
+                                font_size: 24.0,
+                                ..default()
+                            },
+                            TextColor(Color::WHITE),
+                        ));
+                    });
+            }
+        });
+}
+
+/// Pause menu input: mouse clicks on the buttons, or their keyboard shortcuts
+/// Escape/P to resume is handled by toggle_pause
+#[allow(clippy::type_complexity)]
+pub fn handle_pause_menu_input(
+    keyboard: Res<ButtonInput<KeyCode>>,
+    mut button_query: Query<
+        (&Interaction, &PauseButton, &mut BackgroundColor),
+        Changed<Interaction>,
+    >,
+    mut next_app_state: ResMut<NextState<AppState>>,
+    mut next_play_state: ResMut<NextState<PlayState>>,
+    mut message: MessageWriter<PlaySoundEvent>,
+) {
+    let mut pressed = None;
+
+    for (interaction, button, mut color) in button_query.iter_mut() {
+        match interaction {
+            Interaction::Pressed => pressed = Some(*button),
+            Interaction::Hovered => *color = BackgroundColor(BUTTON_HOVER_COLOR),
+            Interaction::None => *color = BackgroundColor(BUTTON_COLOR),
+        }
+    }
+
+    if keyboard.just_pressed(KeyCode::KeyR) {
+        pressed = Some(PauseButton::Restart);
+    } else if keyboard.just_pressed(KeyCode::KeyQ) {
+        pressed = Some(PauseButton::Quit);
+    }
+
+    match pressed {
+        Some(PauseButton::Resume) => next_play_state.set(PlayState::Running),
+        Some(PauseButton::Restart) => {
+            message.write(PlaySoundEvent::GameStart);
+            next_app_state.set(AppState::Restarting);
+        }
+        Some(PauseButton::Quit) => {
+            message.write(PlaySoundEvent::MenuBoop);
+            next_app_state.set(AppState::Menu);
+        }
+        None => {}
+    }
+}
@@ -2,6 +2,7 @@ use crate::components::*;
 use crate::events::*;
 use crate::resources::*;
 use avian2d::prelude::*;
+use bevy::image::{ImageFormatSetting, ImageLoaderSettings};
 use bevy::prelude::*;
 use bevy_hanabi::prelude::*;
 
@@ -55,51 +56,60 @@ fn create_thruster_effect(effects: &mut ResMut<Assets<EffectAsset>>) -> Handle<E
     effects.add(effect)
 }
 
-/// Spawn the player entity
+/// Spawn the player entity, built from the ship picked on the selection screen
 pub fn spawn_player(
     mut commands: Commands,
     asset_server: Res<AssetServer>,
     config: Res<PhysicsConfig>,
+    settings: Res<PlayerSettings>,
     mut effects: ResMut<Assets<EffectAsset>>,
 ) {
+    let ship = settings.ship_type;
+    let stats = ship.stats(&config);
+
     // Create thruster effect
     let thruster_effect = create_thruster_effect(&mut effects);
     let thruster_sound: Handle<AudioSource> =
         asset_server.load("audio/kenney_sci-fi-sounds/Audio/thrusterFire_004.ogg");
 
+    let [left_main, right_main] = ship.main_thrusters();
+    let [left_reverse, right_reverse] = ship.reverse_thrusters();
+
     commands
         .spawn((
-            // Visual representation
             // Visual representation
             Sprite {
-                image: asset_server.load("sprites/ship_G.png"),
-                custom_size: Some(Vec2::new(40.0, 40.0)),
+                image: load_ship_sprite(&asset_server, ship),
+                custom_size: Some(ship.sprite_size()),
                 ..default()
             },
             // Game components
             Player,
-            Health::new(100.0),
+            Health::new(ship.max_health()),
+            ship.resistances(),
+            Invulnerability::default(),
+            Weapon::new(WeaponType::Blaster, &stats),
+            stats,
             // Physics components
             RigidBody::Dynamic,
-            Collider::triangle(
-                Vec2::new(0.0, 20.0),
-                Vec2::new(-20.0, -15.0),
-                Vec2::new(20.0, -15.0),
-            ),
-            Mass(15.0),
+            ship.collider(),
+            Mass(ship.mass()),
             CollisionEventsEnabled,
+            CollidingEntities::default(), // for damage while pressed against a rock
             // Physics config
             ConstantForce::default(),
             ConstantTorque::default(),
             LinearDamping(config.drag),
             AngularDamping(config.angular_drag),
+            // Gone as soon as the run ends
+            DespawnOnExit(AppState::Playing),
         ))
         .with_children(|children| {
             // Left main thruster (under left wing, fires backward)
             children.spawn((
                 Name::new("LeftMainThruster"),
                 ParticleEffect::new(thruster_effect.clone()),
-                Transform::from_translation(Vec3::new(-12.0, -15.0, -1.0)),
+                Transform::from_translation(left_main.extend(-1.0)),
                 Thruster::Left,
                 ThrusterType::Main,
             ));
@@ -108,7 +118,7 @@ pub fn spawn_player(
             children.spawn((
                 Name::new("RightMainThruster"),
                 ParticleEffect::new(thruster_effect.clone()),
-                Transform::from_translation(Vec3::new(12.0, -15.0, -1.0)),
+                Transform::from_translation(right_main.extend(-1.0)),
                 Thruster::Right,
                 ThrusterType::Main,
             ));
@@ -117,7 +127,7 @@ pub fn spawn_player(
             children.spawn((
                 Name::new("LeftReverseThruster"),
                 ParticleEffect::new(thruster_effect.clone()),
-                Transform::from_translation(Vec3::new(-10.0, 15.0, -1.0))
+                Transform::from_translation(left_reverse.extend(-1.0))
                     .with_rotation(Quat::from_rotation_z(std::f32::consts::PI)), // Rotate 180°
                 Thruster::Left,
                 ThrusterType::Reverse,
@@ -127,7 +137,7 @@ pub fn spawn_player(
             children.spawn((
                 Name::new("RightReverseThruster"),
                 ParticleEffect::new(thruster_effect),
-                Transform::from_translation(Vec3::new(10.0, 15.0, -1.0))
+                Transform::from_translation(right_reverse.extend(-1.0))
                     .with_rotation(Quat::from_rotation_z(std::f32::consts::PI)), // Rotate 180°
                 Thruster::Right,
                 ThrusterType::Reverse,
@@ -142,18 +152,35 @@ pub fn spawn_player(
         });
 }
 
-/// Handle player movement with keyboard input
+/// Load a ship sprite
+///
+/// Some of the ship art is saved as JPEG behind a .png name,
+/// so let the loader sniff the real format instead of trusting the extension
+pub fn load_ship_sprite(asset_server: &AssetServer, ship: ShipType) -> Handle<Image> {
+    asset_server.load_with_settings(ship.sprite_path(), |settings: &mut ImageLoaderSettings| {
+        settings.format = ImageFormatSetting::Guess;
+    })
+}
+
+/// Handle player movement from this tick's ShipControls
 ///
 /// Rust Concept: Multiple query parameters
 /// We can query different entity sets in the same system
 pub fn player_movement(
-    keyboard: Res<ButtonInput<KeyCode>>,
-    physics_config: Res<PhysicsConfig>,
-    mut query: Query<(&mut ConstantForce, &mut ConstantTorque, &Transform), With<Player>>,
+    controls: Res<ShipControls>,
+    mut query: Query<
+        (
+            &mut ConstantForce,
+            &mut ConstantTorque,
+            &Transform,
+            &ShipStats,
+        ),
+        With<Player>,
+    >,
 ) {
     // Rust Concept: Early return pattern
     // If no player exists, just return
-    let Ok((mut constant_force, mut constant_torque, transform)) = query.single_mut() else {
+    let Ok((mut constant_force, mut constant_torque, transform, stats)) = query.single_mut() else {
         return;
     };
 
@@ -163,67 +190,34 @@ pub fn player_movement(
     constant_force.0 = Vec2::ZERO;
     constant_torque.0 = 0.0;
 
-    // which thrusters are active
-    let mut left_thruster_active = false;
-    let mut right_thruster_active = false;
-    let mut reverse_active = false;
-
-    // Rust Concept: if expressions (not statements)
-    // left arrow fires right thruster
-    if keyboard.pressed(KeyCode::ArrowLeft) || keyboard.pressed(KeyCode::KeyA) {
-        right_thruster_active = true;
-    }
-    if keyboard.pressed(KeyCode::ArrowRight) || keyboard.pressed(KeyCode::KeyD) {
-        left_thruster_active = true;
-    }
-    if keyboard.pressed(KeyCode::ArrowUp) || keyboard.pressed(KeyCode::KeyW) {
-        left_thruster_active = true;
-        right_thruster_active = true;
-    }
-    if keyboard.pressed(KeyCode::ArrowDown) || keyboard.pressed(KeyCode::KeyS) {
-        reverse_active = true;
-    }
-
     // Apply Physics
 
     let forward = (transform.rotation * Vec3::Y).truncate();
 
+    // thrusters push in proportion to how hard they're driven
+
     //left thruster
-    if left_thruster_active {
-        constant_force.0 += forward * physics_config.thruster_force;
-        constant_torque.0 -= physics_config.rotation_torque;
-    }
+    constant_force.0 += forward * stats.thruster_force * controls.left_thruster;
+    constant_torque.0 -= stats.rotation_torque * controls.left_thruster;
 
     //right thruster
-    if right_thruster_active {
-        constant_force.0 += forward * physics_config.thruster_force;
-        constant_torque.0 += physics_config.rotation_torque;
-    }
+    constant_force.0 += forward * stats.thruster_force * controls.right_thruster;
+    constant_torque.0 += stats.rotation_torque * controls.right_thruster;
 
     //reverse thruster
-    if reverse_active {
-        constant_force.0 -= forward * physics_config.reverse_thrust_force;
-    }
+    constant_force.0 -= forward * stats.reverse_thrust_force * controls.reverse;
 }
 
 pub fn update_thruster_audio(
-    keyboard: Res<ButtonInput<KeyCode>>,
+    controls: Res<ShipControls>,
     query: Query<&AudioSink, With<ThrusterAudio>>,
 ) {
     // We expect only one thruster audio entity
     let Ok(sink) = query.single() else {
         return;
     };
-    // Check if any thruster key is pressed
-    let is_thrusting = keyboard.pressed(KeyCode::ArrowUp)
-        || keyboard.pressed(KeyCode::KeyW)
-        || keyboard.pressed(KeyCode::ArrowDown)
-        || keyboard.pressed(KeyCode::KeyS)
-        || keyboard.pressed(KeyCode::ArrowLeft)
-        || keyboard.pressed(KeyCode::KeyA)
-        || keyboard.pressed(KeyCode::ArrowRight)
-        || keyboard.pressed(KeyCode::KeyD);
-    if is_thrusting {
+    // Check if any thruster is firing
+    if controls.is_thrusting() {
         if sink.is_paused() {
             sink.play();
         }
@@ -232,43 +226,84 @@ pub fn update_thruster_audio(
     }
 }
 
-/// Handle player firing
+/// Handle player firing and weapon switching
+///
+/// Holding fire auto-fires as fast as the weapon's cooldown allows, the beam
+/// charges while held and fires on release
 pub fn player_fire(
     mut commands: Commands,
-    keyboard: Res<ButtonInput<KeyCode>>,
-    query: Query<&Transform, With<Player>>,
-    config: Res<PhysicsConfig>,
+    controls: Res<ShipControls>,
+    time: Res<Time>,
+    mut query: Query<(&Transform, &LinearVelocity, &ShipStats, &mut Weapon), With<Player>>,
+    mut director: ResMut<Director>,
     mut message: MessageWriter<PlaySoundEvent>,
 ) {
-    if !keyboard.just_pressed(KeyCode::Space) {
+    let Ok((transform, ship_velocity, stats, mut weapon)) = query.single_mut() else {
+        return;
+    };
+
+    if controls.switch_weapon {
+        *weapon = Weapon::new(weapon.weapon_type.next(), stats);
+        message.write(PlaySoundEvent::MenuBoop);
         return;
     }
 
-    let Ok(transform) = query.single() else {
+    let Some(power) = weapon.trigger(controls.fire, controls.fire_pressed, time.delta_secs())
+    else {
         return;
     };
+    // one pull of the trigger is one shot to the director, however many pellets it fires
+    director.shots_fired += 1;
 
-    // Spawn projectile at ship's nose
-    // Offset slightly forward so it doesn't spawn inside the ship
+    // Spawn projectiles at ship's nose
+    // Offset slightly forward so they don't spawn inside the ship
     let forward = (transform.rotation * Vec3::Y).truncate();
-    let spawn_pos = transform.translation.truncate() + forward * 30.0;
+    let spawn_pos = transform.translation.truncate() + forward * stats.muzzle_offset;
 
-    commands.spawn((
-        Sprite {
-            // Use a small circle or existing asset
-            // For now, we'll use a small custom size sprite if no asset
-            custom_size: Some(Vec2::new(10.0, 20.0)),
-            color: Color::srgb(1.0, 0.8, 0.2),
-            ..default()
-        },
-        Transform::from_translation(spawn_pos.extend(0.0)).with_rotation(transform.rotation),
-        Projectile,
-        Lifetime::new(config.projectile_lifetime),
-        // Physics for collision detection
-        RigidBody::Kinematic, // Kinematic so it moves manually but detects collisions
-        Collider::rectangle(10.0, 20.0),
-        Sensor, // Sensor so it doesn't physically push things
-    ));
+    // a half-charged beam is shorter as well as weaker
+    let size = if weapon.charge_time > 0.0 {
+        Vec2::new(weapon.projectile_size.x, weapon.projectile_size.y * power)
+    } else {
+        weapon.projectile_size
+    };
+    let color = if weapon.piercing {
+        Color::srgb(0.4, 0.9, 1.0)
+    } else {
+        Color::srgb(1.0, 0.8, 0.2)
+    };
+
+    // Rust Concept: Spreading shots evenly
+    // n shots split the spread into n - 1 gaps, centred on the nose
+    for i in 0..weapon.projectile_count {
+        let angle = if weapon.projectile_count > 1 {
+            weapon.spread * (i as f32 / (weapon.projectile_count - 1) as f32 - 0.5)
+        } else {
+            0.0
+        };
+        let rotation = transform.rotation * Quat::from_rotation_z(angle);
+        // shots leave the ship at the weapon's speed on top of the ship's own
+        let velocity = (rotation * Vec3::Y).truncate() * weapon.projectile_speed + ship_velocity.0;
+
+        commands.spawn((
+            Sprite {
+                custom_size: Some(size),
+                color,
+                ..default()
+            },
+            Transform::from_translation(spawn_pos.extend(0.0)).with_rotation(rotation),
+            Projectile {
+                share: 1.0 / weapon.projectile_count as f32,
+                ..Projectile::new(weapon.damage * power, weapon.piercing)
+            },
+            Lifetime::new(weapon.projectile_lifetime),
+            // Physics moves it, sweep_projectiles finds what it hits
+            RigidBody::Kinematic, // Kinematic so it flies at its velocity whatever it meets
+            LinearVelocity(velocity),
+            Collider::rectangle(size.x, size.y),
+            Sensor, // Sensor so it doesn't physically push things
+            DespawnOnExit(AppState::Playing),
+        ));
+    }
     message.write(PlaySoundEvent::Laser);
 }
 
@@ -292,92 +327,63 @@ pub fn constrain_player_position(mut query: Query<&mut Transform, With<Player>>)
     transform.translation.y = transform.translation.y.clamp(MIN_Y, MAX_Y);
 }
 
-/// Display player health (simple text for now)
-///
-/// Rust Concept: One-shot systems for UI setup
-pub fn setup_health_display(mut commands: Commands) {
+/// Show which weapon is selected, under the hull bar
+pub fn setup_weapon_display(mut commands: Commands) {
     commands.spawn((
-        Text::new("Health: 100"),
+        Text::new(""),
         TextFont {
-            font_size: 30.0,
+            font_size: 20.0,
             ..default()
         },
-        TextColor(Color::WHITE),
+        TextColor(Color::srgb(0.8, 0.8, 0.9)),
         Node {
             position_type: PositionType::Absolute,
-            top: Val::Px(10.0),
+            top: Val::Px(46.0),
             left: Val::Px(10.0),
             ..default()
         },
-        HealthDisplay,
+        WeaponDisplay,
     ));
 }
 
-/// Marker component for health UI
+/// Marker component for weapon UI
 #[derive(Component)]
-pub(crate) struct HealthDisplay;
+pub(crate) struct WeaponDisplay;
 
-/// Update health display
-///
-/// Rust Concept: Optional query results
-/// Using Option<> for queries that might not have results
-pub fn update_health_display(
-    player_query: Query<&Health, With<Player>>,
-    mut text_query: Query<&mut Text, With<HealthDisplay>>,
-    app_state: Res<State<AppState>>,
+/// Update weapon display, with the charge while the beam is charging
+pub fn update_weapon_display(
+    player_query: Query<&Weapon, With<Player>>,
+    mut text_query: Query<&mut Text, With<WeaponDisplay>>,
 ) {
     let Ok(mut text) = text_query.single_mut() else {
         return;
     };
 
-    if let Ok(health) = player_query.single() {
-        // Player is alive, show current health
-        *text = Text::new(format!("Health: {:.0}", health.current()));
-    } else if *app_state.get() != AppState::Playing {
-        // Player is dead (despawned), show 0
-        *text = Text::new("Health: 0");
-    }
+    *text = match player_query.single() {
+        Ok(weapon) if weapon.charge_fraction() > 0.0 => Text::new(format!(
+            "{} {:.0}%",
+            weapon.weapon_type.display_name(),
+            weapon.charge_fraction() * 100.0
+        )),
+        Ok(weapon) => Text::new(weapon.weapon_type.display_name()),
+        Err(_) => Text::new(""),
+    };
 }
 
-/// Update thruster particle effects based on input
+/// Update thruster particle effects based on ShipControls
 pub fn update_thruster_visuals(
-    keyboard: Res<ButtonInput<KeyCode>>,
+    controls: Res<ShipControls>,
     mut query: Query<(&Thruster, &ThrusterType, &mut Visibility)>,
 ) {
-    let mut left_main_active = false;
-    let mut right_main_active = false;
-    let mut left_reverse_active = false;
-    let mut right_reverse_active = false;
-
-    // Logic matches player_movement
-    // Left Arrow -> Fires Right Thruster
-    if keyboard.pressed(KeyCode::ArrowLeft) || keyboard.pressed(KeyCode::KeyA) {
-        right_main_active = true;
-    }
-    // Right Arrow -> Fires Left Thruster
-    if keyboard.pressed(KeyCode::ArrowRight) || keyboard.pressed(KeyCode::KeyD) {
-        left_main_active = true;
-    }
-    // Up Arrow -> Fires BOTH main thrusters
-    if keyboard.pressed(KeyCode::ArrowUp) || keyboard.pressed(KeyCode::KeyW) {
-        left_main_active = true;
-        right_main_active = true;
-    }
-    // Down Arrow -> Fires BOTH reverse thrusters
-    if keyboard.pressed(KeyCode::ArrowDown) || keyboard.pressed(KeyCode::KeyS) {
-        left_reverse_active = true;
-        right_reverse_active = true;
-    }
-
     for (thruster, thruster_type, mut visibility) in query.iter_mut() {
-        let active = match (thruster, thruster_type) {
-            (Thruster::Left, ThrusterType::Main) => left_main_active,
-            (Thruster::Right, ThrusterType::Main) => right_main_active,
-            (Thruster::Left, ThrusterType::Reverse) => left_reverse_active,
-            (Thruster::Right, ThrusterType::Reverse) => right_reverse_active,
+        // both reverse thrusters fire together
+        let strength = match (thruster, thruster_type) {
+            (Thruster::Left, ThrusterType::Main) => controls.left_thruster,
+            (Thruster::Right, ThrusterType::Main) => controls.right_thruster,
+            (_, ThrusterType::Reverse) => controls.reverse,
         };
 
-        *visibility = if active {
+        *visibility = if strength > 0.0 {
             Visibility::Visible
         } else {
             Visibility::Hidden
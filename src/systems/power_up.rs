@@ -0,0 +1,199 @@
+//! Power-ups: pickups that drift down the screen and what they do once collected
+//!
+//! The shield is a force field around the ship that soaks up damage in
+//! handle_health_message before the ship's Health sees it. Score gems are left
+//! behind by crystal asteroids and are worth points straight away.
+
+use crate::components::*;
+use crate::events::PlaySoundEvent;
+use crate::resources::*;
+use avian2d::prelude::*;
+use bevy::prelude::*;
+use rand::Rng;
+
+const FORCE_FIELD_IMAGE: &str = "force_field.jpg";
+
+/// Drop a power-up in from the top every so often
+#[allow(clippy::too_many_arguments)]
+pub fn spawn_power_ups(
+    mut commands: Commands,
+    mut timer: ResMut<PowerUpTimer>,
+    config: Res<PowerUpConfig>,
+    spawn_config: Res<AsteroidSpawnConfig>,
+    time: Res<Time>,
+    asset_server: Res<AssetServer>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+    mut game_rng: ResMut<GameRng>,
+) {
+    if !timer.0.tick(time.delta()).just_finished() {
+        return;
+    }
+
+    let x = game_rng
+        .pickups
+        .random_range(-spawn_config.spawn_x_range..spawn_config.spawn_x_range);
+
+    // Rust Concept: Textured meshes
+    // The force field image is a square jpeg with no transparency, drawing it
+    // on a circle mesh crops away the corners
+    commands.spawn((
+        Mesh2d(meshes.add(Circle::new(config.radius))),
+        MeshMaterial2d(materials.add(ColorMaterial {
+            texture: Some(asset_server.load(FORCE_FIELD_IMAGE)),
+            ..default()
+        })),
+        Transform::from_xyz(x, spawn_config.spawn_y, 0.0),
+        PowerUp::Shield,
+        Cleanup,
+        // Kinematic sensor: drifts at a set speed and never shoves anything
+        RigidBody::Kinematic,
+        Collider::circle(config.radius),
+        Sensor,
+        CollisionEventsEnabled,
+        LinearVelocity(Vec2::new(0.0, -config.speed)),
+        AngularVelocity(1.0),
+        DespawnOnExit(AppState::Playing),
+    ));
+}
+
+/// Leave a gem where a crystal asteroid broke, it drifts off like any other pickup
+pub fn spawn_score_gem(
+    commands: &mut Commands,
+    meshes: &mut ResMut<Assets<Mesh>>,
+    materials: &mut ResMut<Assets<ColorMaterial>>,
+    config: &PowerUpConfig,
+    position: Vec3,
+) {
+    let radius = config.radius * 0.75;
+    commands.spawn((
+        Mesh2d(meshes.add(RegularPolygon::new(radius, 6))),
+        MeshMaterial2d(materials.add(ColorMaterial::from(AsteroidMaterial::Crystal.color()))),
+        Transform::from_translation(position),
+        PowerUp::ScoreBonus(config.crystal_bonus),
+        Cleanup,
+        RigidBody::Kinematic,
+        Collider::circle(radius),
+        Sensor,
+        CollisionEventsEnabled,
+        LinearVelocity(Vec2::new(0.0, -config.speed)),
+        AngularVelocity(2.0),
+        DespawnOnExit(AppState::Playing),
+    ));
+}
+
+/// Fly into a power-up to collect it
+/// Picking up a shield while one is up tops the existing one back up
+#[allow(clippy::too_many_arguments)]
+pub fn collect_power_ups(
+    mut commands: Commands,
+    mut collision_events: MessageReader<CollisionStart>,
+    player_query: Query<Entity, With<Player>>,
+    power_up_query: Query<&PowerUp>,
+    mut shield_query: Query<&mut Shield>,
+    config: Res<PowerUpConfig>,
+    settings: Res<PlayerSettings>,
+    mut game_data: ResMut<GameData>,
+    asset_server: Res<AssetServer>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+    mut message: MessageWriter<PlaySoundEvent>,
+) {
+    let Ok(player) = player_query.single() else {
+        return;
+    };
+    // a shield spawned this tick isn't in shield_query until the commands apply
+    let mut shield_spawned = false;
+
+    for event in collision_events.read() {
+        let (Some(body1), Some(body2)) = (event.body1, event.body2) else {
+            continue;
+        };
+        let pickup = if body1 == player {
+            body2
+        } else if body2 == player {
+            body1
+        } else {
+            continue;
+        };
+        let Ok(power_up) = power_up_query.get(pickup) else {
+            continue;
+        };
+
+        commands.entity(pickup).despawn();
+
+        match *power_up {
+            PowerUp::ScoreBonus(points) => {
+                game_data.score += points;
+                message.write(PlaySoundEvent::MenuBoop);
+            }
+            PowerUp::Shield => {
+                message.write(PlaySoundEvent::ShieldUp);
+                let shield = Shield::new(config.shield_hit_points, config.shield_duration);
+                if let Ok(mut existing) = shield_query.single_mut() {
+                    *existing = shield;
+                    continue;
+                }
+                // the one on its way is already at full strength
+                if shield_spawned {
+                    continue;
+                }
+                shield_spawned = true;
+
+                // a bubble a little bigger than the ship
+                let radius = settings.ship_type.sprite_size().max_element() * 0.75;
+                let field = commands
+                    .spawn((
+                        Name::new("Shield"),
+                        Mesh2d(meshes.add(Circle::new(radius))),
+                        MeshMaterial2d(materials.add(ColorMaterial {
+                            color: Color::srgba(1.0, 1.0, 1.0, 0.7),
+                            texture: Some(asset_server.load(FORCE_FIELD_IMAGE)),
+                            alpha_mode: AlphaMode2d::Blend,
+                            ..default()
+                        })),
+                        Transform::from_xyz(0.0, 0.0, 1.0),
+                        shield,
+                    ))
+                    .id();
+                commands.entity(player).add_child(field);
+            }
+        }
+    }
+}
+
+/// Count the shield down, fade it as it weakens and take it away when it's spent
+pub fn update_shields(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut shield_query: Query<(Entity, &mut Shield, &MeshMaterial2d<ColorMaterial>)>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+    mut message: MessageWriter<PlaySoundEvent>,
+) {
+    for (entity, mut shield, material) in shield_query.iter_mut() {
+        shield.remaining.tick(time.delta());
+        shield.flash.tick(time.delta());
+
+        if shield.is_down() {
+            commands.entity(entity).despawn();
+            message.write(PlaySoundEvent::ShieldDown);
+            continue;
+        }
+
+        // weaker shields are fainter, and it blinks when it's about to run out
+        let mut alpha = 0.2 + 0.5 * shield.strength();
+        let seconds_left = shield.remaining.remaining_secs();
+        if seconds_left < 3.0 && (seconds_left * 4.0).fract() < 0.5 {
+            alpha *= 0.3;
+        }
+        let color = if shield.flash.is_finished() {
+            Color::srgba(1.0, 1.0, 1.0, alpha)
+        } else {
+            Color::srgba(1.0, 0.4, 0.4, alpha) // just took a hit
+        };
+
+        if let Some(material) = materials.get_mut(&material.0) {
+            material.color = color;
+        }
+    }
+}
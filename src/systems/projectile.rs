@@ -1,17 +1,69 @@
-use bevy::prelude::*;
 use crate::components::*;
-use crate::resources::*;
+use crate::events::ProjectileHit;
+use avian2d::prelude::*;
+use bevy::prelude::*;
 
-/// Move projectiles forward
-pub fn move_projectiles(
-    mut query: Query<(&mut Transform, &Projectile)>,
+/// Most rocks one shot can find in a single tick
+const MAX_SWEEP_HITS: u32 = 8;
+
+/// Find the asteroids each shot reaches this tick, before physics moves it
+///
+/// Rust Concept: Swept collision detection
+/// A fast shot can travel further in one tick than a small rock is wide, so
+/// checking where it ends up would let it skip straight past. Casting its shape
+/// along the whole path it's about to fly finds everything in between.
+pub fn sweep_projectiles(
+    spatial_query: SpatialQuery,
+    mut projectiles: Query<(
+        Entity,
+        &Transform,
+        &LinearVelocity,
+        &Collider,
+        &mut Projectile,
+    )>,
+    asteroids: Query<(), With<Asteroid>>,
     time: Res<Time>,
-    config: Res<PhysicsConfig>,
+    mut hits: MessageWriter<ProjectileHit>,
 ) {
-    for (mut transform, _) in query.iter_mut() {
-        // Projectiles move in their local Y direction (forward)
-        let forward = (transform.rotation * Vec3::Y).truncate();
-        transform.translation += (forward * config.projectile_speed * time.delta_secs()).extend(0.0);
+    for (entity, transform, velocity, collider, mut projectile) in projectiles.iter_mut() {
+        let Ok(direction) = Dir2::new(velocity.0) else {
+            continue;
+        };
+
+        // the transform rather than Position, which a shot fired this tick doesn't have yet
+        let mut found = spatial_query.shape_hits(
+            collider,
+            transform.translation.truncate(),
+            transform.rotation.to_euler(EulerRot::XYZ).2,
+            direction,
+            MAX_SWEEP_HITS,
+            &ShapeCastConfig::from_max_distance(velocity.length() * time.delta_secs()),
+            &SpatialQueryFilter::from_excluded_entities([entity]),
+        );
+        found.retain(|hit| {
+            asteroids.contains(hit.entity) && !projectile.already_hit.contains(&hit.entity)
+        });
+        // nearest first, a normal shot stops at the first rock in its way
+        found.sort_by(|a, b| a.distance.total_cmp(&b.distance));
+        if !projectile.piercing {
+            found.truncate(1);
+        }
+
+        for hit in found {
+            let share = if projectile.already_hit.is_empty() {
+                projectile.share
+            } else {
+                0.0
+            };
+            projectile.already_hit.push(hit.entity);
+            hits.write(ProjectileHit {
+                projectile: entity,
+                asteroid: hit.entity,
+                point: hit.point2,
+                velocity: velocity.0,
+                share,
+            });
+        }
     }
 }
 
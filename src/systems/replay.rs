@@ -0,0 +1,264 @@
+//! Recording runs and playing them back
+//!
+//! A live run records its ShipControls every simulation tick. Together with the
+//! seed, the ship, the tick rate and a fingerprint of the tuning files that's
+//! everything needed to fly it again: playback feeds the recorded controls to the
+//! same FixedUpdate systems.
+
+use crate::resources::*;
+use crate::storage;
+use bevy::prelude::*;
+
+const LAST_REPLAY_KEY: &str = "last_replay.txt";
+const REPLAY_HEADER: &str = "asteroid_dodge replay 4";
+/// The longest replay we'll load, two hours of play, so a corrupt file can't eat all the memory
+const MAX_REPLAY_TICKS: usize = SIMULATION_HZ as usize * 60 * 60 * 2;
+
+/// Load a replay to watch from the menu: `--replay <file>` if given, otherwise the last run
+pub fn load_replay(mut loaded: ResMut<LoadedReplay>) {
+    let args: Vec<String> = std::env::args().collect();
+    let contents = match command_line_value(&args, "--replay") {
+        Some(path) => match std::fs::read_to_string(&path) {
+            Ok(contents) => Some(contents),
+            Err(e) => {
+                warn!("Could not read replay {path}: {e}");
+                None
+            }
+        },
+        None => storage::read(LAST_REPLAY_KEY),
+    };
+
+    let Some(contents) = contents else {
+        return;
+    };
+    match decode_replay(&contents) {
+        Ok(replay) => loaded.0 = Some(replay),
+        Err(e) => warn!("Ignoring replay: {e}"),
+    }
+}
+
+/// First system of every simulation tick during playback: this tick's recorded controls
+/// When the recording runs out the replay is over
+pub fn feed_replay_input(
+    mut run_mode: ResMut<RunMode>,
+    mut controls: ResMut<ShipControls>,
+    mut next_state: ResMut<NextState<AppState>>,
+) {
+    let RunMode::Replay { replay, tick } = &mut *run_mode else {
+        return;
+    };
+
+    match replay.inputs.get(*tick) {
+        Some(recorded) => {
+            *controls = *recorded;
+            *tick += 1;
+        }
+        None => {
+            *controls = ShipControls::default();
+            next_state.set(AppState::GameOver);
+        }
+    }
+}
+
+/// Store this tick's controls, runs right after they are decided
+pub fn record_input(
+    run_mode: Res<RunMode>,
+    controls: Res<ShipControls>,
+    mut recording: ResMut<InputRecording>,
+) {
+    if !run_mode.is_replay() {
+        recording.0.inputs.push(*controls);
+    }
+}
+
+/// Save a finished live run as the last replay, runs on OnEnter(AppState::GameOver)
+pub fn save_replay(
+    run_mode: Res<RunMode>,
+    recording: Res<InputRecording>,
+    mut loaded: ResMut<LoadedReplay>,
+) {
+    if run_mode.is_replay() {
+        return;
+    }
+
+    if let Err(e) = storage::write(LAST_REPLAY_KEY, &encode_replay(&recording.0)) {
+        warn!("Could not save replay: {e}");
+    }
+    loaded.0 = Some(recording.0.clone());
+}
+
+// thruster strengths as whole steps so they survive the trip through text exactly,
+// then the buttons packed one bit each
+fn encode_controls(controls: &ShipControls) -> String {
+    let steps = |strength: f32| (strength * ShipControls::THRUST_STEPS).round() as u8;
+    let buttons = (controls.fire as u8)
+        | ((controls.fire_pressed as u8) << 1)
+        | ((controls.switch_weapon as u8) << 2);
+    format!(
+        "{},{},{},{}",
+        steps(controls.left_thruster),
+        steps(controls.right_thruster),
+        steps(controls.reverse),
+        buttons
+    )
+}
+
+fn decode_controls(text: &str) -> Option<ShipControls> {
+    let mut values = text.split(',').map(|value| value.parse::<u8>().ok());
+    let mut strength = || Some(f32::from(values.next()??) / ShipControls::THRUST_STEPS);
+    let left_thruster = strength()?;
+    let right_thruster = strength()?;
+    let reverse = strength()?;
+    let buttons = values.next()??;
+    let controls = ShipControls {
+        left_thruster,
+        right_thruster,
+        reverse,
+        fire: buttons & 1 != 0,
+        fire_pressed: buttons & (1 << 1) != 0,
+        switch_weapon: buttons & (1 << 2) != 0,
+    };
+    // nothing left over
+    values.next().is_none().then_some(controls)
+}
+
+/// Plain text: a header, then the inputs run-length encoded as
+/// `<count>x<left>,<right>,<reverse>,<buttons>`
+///
+/// Rust Concept: Run-length encoding with chunk_by
+/// Players hold keys for many ticks, so runs of identical input collapse well
+pub fn encode_replay(replay: &Replay) -> String {
+    let runs: Vec<String> = replay
+        .inputs
+        .chunk_by(|a, b| a == b)
+        .map(|run| format!("{}x{}", run.len(), encode_controls(&run[0])))
+        .collect();
+
+    format!(
+        "{REPLAY_HEADER}\nseed {}\nship {:?}\nhz {}\ntuning {:016x}\ninputs {}\n",
+        replay.seed,
+        replay.ship_type,
+        replay.timestep_hz,
+        replay.tuning,
+        runs.join(" ")
+    )
+}
+
+/// Parse a replay, with an error that says which part was wrong
+pub fn decode_replay(contents: &str) -> Result<Replay, String> {
+    let mut lines = contents.lines();
+    if lines.next() != Some(REPLAY_HEADER) {
+        return Err("not an asteroid_dodge replay (or a newer version)".to_string());
+    }
+
+    let seed = field(&mut lines, "seed")?
+        .parse()
+        .map_err(|_| "seed is not a number".to_string())?;
+    let ship_name = field(&mut lines, "ship")?;
+    let ship_type = ShipType::ALL
+        .into_iter()
+        .find(|ship| format!("{ship:?}") == ship_name)
+        .ok_or_else(|| format!("unknown ship {ship_name}"))?;
+    let timestep_hz: f64 = field(&mut lines, "hz")?
+        .parse()
+        .map_err(|_| "hz is not a number".to_string())?;
+    // live runs always record at the simulation rate, anything else is damaged
+    if timestep_hz != SIMULATION_HZ {
+        return Err(format!("hz must be {SIMULATION_HZ}"));
+    }
+    let tuning = u64::from_str_radix(field(&mut lines, "tuning")?, 16)
+        .map_err(|_| "tuning is not a fingerprint".to_string())?;
+
+    let mut inputs = Vec::new();
+    for run in field(&mut lines, "inputs")?.split_whitespace() {
+        let bad_run = || format!("bad input run {run}");
+        let (count, controls) = run.split_once('x').ok_or_else(bad_run)?;
+        let count: usize = count.parse().map_err(|_| bad_run())?;
+        let controls = decode_controls(controls).ok_or_else(bad_run)?;
+        if count > MAX_REPLAY_TICKS - inputs.len() {
+            return Err(format!("longer than {MAX_REPLAY_TICKS} ticks"));
+        }
+        inputs.extend(std::iter::repeat_n(controls, count));
+    }
+
+    Ok(Replay {
+        seed,
+        ship_type,
+        timestep_hz,
+        tuning,
+        inputs,
+    })
+}
+
+// next line must be `<name> <value>`
+fn field<'a>(lines: &mut std::str::Lines<'a>, name: &str) -> Result<&'a str, String> {
+    lines
+        .next()
+        .and_then(|line| line.strip_prefix(name))
+        .map(str::trim)
+        .ok_or_else(|| format!("missing {name}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_replay_round_trip() {
+        let thrust = ShipControls {
+            left_thruster: 1.0,
+            right_thruster: ShipControls::quantize(0.2),
+            ..default()
+        };
+        let fire = ShipControls {
+            fire: true,
+            fire_pressed: true,
+            ..default()
+        };
+
+        let mut replay = Replay::new(987, ShipType::Rocket, SIMULATION_HZ, 0xfeed);
+        replay.inputs = vec![ShipControls::default(), thrust, thrust, thrust, fire];
+
+        let encoded = encode_replay(&replay);
+        assert!(encoded.contains("tuning 000000000000feed"));
+        assert!(encoded.contains("inputs 1x0,0,0,0 3x255,51,0,0 1x0,0,0,3"));
+        assert_eq!(decode_replay(&encoded), Ok(replay));
+    }
+
+    #[test]
+    fn test_bad_replays_are_errors() {
+        assert!(decode_replay("hello").is_err());
+        assert!(decode_replay(&format!("{REPLAY_HEADER}\nseed x\n")).is_err());
+        assert!(
+            decode_replay(&format!(
+                "{REPLAY_HEADER}\nseed 1\nship Zeppelin\nhz 64\ntuning 0\ninputs \n"
+            ))
+            .is_err()
+        );
+        assert!(
+            decode_replay(&format!(
+                "{REPLAY_HEADER}\nseed 1\nship Rocket\nhz 64\ntuning 0\ninputs 3y0,0,0,0\n"
+            ))
+            .is_err()
+        );
+    }
+
+    #[test]
+    fn test_damaged_replays_are_refused() {
+        let replay = |hz: &str, inputs: &str| {
+            decode_replay(&format!(
+                "{REPLAY_HEADER}\nseed 1\nship Rocket\nhz {hz}\ntuning 0\ninputs {inputs}\n"
+            ))
+        };
+
+        assert!(replay("64", "2x0,0,0,0").is_ok());
+        // a tick rate Time<Fixed> can't run
+        assert!(replay("NaN", "2x0,0,0,0").is_err());
+        assert!(replay("inf", "2x0,0,0,0").is_err());
+        assert!(replay("0.000001", "2x0,0,0,0").is_err());
+        // refused before anything is allocated
+        assert!(replay("64", "18446744073709551615x0,0,0,0").is_err());
+        let just_over = format!("{MAX_REPLAY_TICKS}x0,0,0,0 1x0,0,0,1");
+        assert!(replay("64", &just_over).is_err());
+    }
+}
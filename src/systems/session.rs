@@ -0,0 +1,54 @@
+//! Run lifecycle
+//!
+//! Gameplay entities carry DespawnOnExit(AppState::Playing) so Bevy clears them
+//! when a run ends. Run resources are put back to their defaults here when the
+//! next run starts, so nothing from the last run leaks into the new one.
+
+use crate::resources::*;
+use crate::systems::config::LoadedTuning;
+use bevy::prelude::*;
+
+/// Reset every per-run resource, runs on OnEnter(AppState::Playing)
+///
+/// Rust Concept: Default as the single source of truth
+/// Re-inserting the default value means new fields get reset for free
+pub fn reset_run(
+    mut commands: Commands,
+    seed_settings: Res<SeedSettings>,
+    player_settings: Res<PlayerSettings>,
+    director_config: Res<DirectorConfig>,
+    tuning: LoadedTuning,
+    mut run_mode: ResMut<RunMode>,
+    mut fixed_time: ResMut<Time<Fixed>>,
+) {
+    // a replay brings its own seed and tick rate, and starts from its first tick
+    let (seed, timestep_hz) = match &mut *run_mode {
+        RunMode::Live => (seed_settings.next_seed(), SIMULATION_HZ),
+        RunMode::Replay { replay, tick } => {
+            *tick = 0;
+            (replay.seed, replay.timestep_hz)
+        }
+    };
+    fixed_time.set_timestep_hz(timestep_hz);
+
+    commands.insert_resource(GameData::default());
+    commands.insert_resource(GameRng::new(seed));
+    commands.insert_resource(ShipControls::default());
+    commands.insert_resource(InputRecording(Replay::new(
+        seed,
+        player_settings.ship_type,
+        timestep_hz,
+        tuning.fingerprint(),
+    )));
+    commands.insert_resource(SpawnTimer::default()); // also resets difficulty progress
+    commands.insert_resource(LevelProgress::default()); // back to the first level
+    commands.insert_resource(Director::new(&director_config));
+    commands.insert_resource(PowerUpTimer::default());
+    commands.insert_resource(ScreenShake::default());
+    commands.insert_resource(CameraTarget::default());
+}
+
+/// Restarting is only ever a one-frame stop on the way back to Playing
+pub fn restart_run(mut next_state: ResMut<NextState<AppState>>) {
+    next_state.set(AppState::Playing);
+}
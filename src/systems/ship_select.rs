@@ -0,0 +1,184 @@
+use crate::events::*;
+use crate::resources::{AppState, PhysicsConfig, PlayerSettings, ShipType};
+use crate::systems::player::load_ship_sprite;
+use bevy::prelude::*;
+
+const SELECTED_BORDER: Color = Color::srgb(1.0, 0.8, 0.2);
+const UNSELECTED_BORDER: Color = Color::srgb(0.3, 0.3, 0.4);
+
+/// Marker component for a ship card, remembers which ship it shows
+#[derive(Component)]
+pub(crate) struct ShipCard(ShipType);
+
+// This spawns the ship selection UI, one card per ShipType
+pub fn setup_ship_select(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    config: Res<PhysicsConfig>,
+    settings: Res<PlayerSettings>,
+) {
+    commands
+        .spawn((
+            Node {
+                width: Val::Percent(100.0),
+                height: Val::Percent(100.0),
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                flex_direction: FlexDirection::Column,
+                row_gap: Val::Px(20.0),
+                ..default()
+            },
+            DespawnOnExit(AppState::ShipSelect),
+        ))
+        .with_children(|parent| {
+            parent.spawn((
+                Text::new("Choose Your Ship"),
+                TextFont {
+                    font_size: 40.0,
+                    ..default()
+                },
+                TextColor(Color::WHITE),
+            ));
+
+            parent
+                .spawn(Node {
+                    flex_direction: FlexDirection::Row,
+                    column_gap: Val::Px(16.0),
+                    ..default()
+                })
+                .with_children(|row| {
+                    for ship in ShipType::ALL {
+                        spawn_ship_card(row, &asset_server, &config, ship, settings.ship_type);
+                    }
+                });
+
+            parent.spawn((
+                Text::new("<Left>/<Right> to choose, <Enter> or tap to launch, <Esc> to go back"),
+                TextFont {
+                    font_size: 18.0,
+                    ..default()
+                },
+                TextColor(Color::WHITE),
+            ));
+        });
+}
+
+// A card with the ship sprite, its name and the numbers that matter
+fn spawn_ship_card(
+    parent: &mut ChildSpawnerCommands,
+    asset_server: &AssetServer,
+    config: &PhysicsConfig,
+    ship: ShipType,
+    selected: ShipType,
+) {
+    let stats = ship.stats(config);
+    let border = if ship == selected {
+        SELECTED_BORDER
+    } else {
+        UNSELECTED_BORDER
+    };
+
+    parent
+        .spawn((
+            Node {
+                width: Val::Px(200.0),
+                flex_direction: FlexDirection::Column,
+                align_items: AlignItems::Center,
+                padding: UiRect::all(Val::Px(12.0)),
+                row_gap: Val::Px(8.0),
+                border: UiRect::all(Val::Px(3.0)),
+                ..default()
+            },
+            BorderColor::all(border),
+            // a button so the card can be clicked or tapped
+            Button,
+            ShipCard(ship),
+        ))
+        .with_children(|card| {
+            card.spawn((
+                ImageNode::new(load_ship_sprite(asset_server, ship)),
+                Node {
+                    width: Val::Px(64.0),
+                    height: Val::Px(64.0),
+                    ..default()
+                },
+            ));
+            card.spawn((
+                Text::new(ship.display_name()),
+                TextFont {
+                    font_size: 24.0,
+                    ..default()
+                },
+                TextColor(Color::WHITE),
+            ));
+            card.spawn((
+                Text::new(format!(
+                    "Health {:.0}\nMass {:.0}\nThrust {:.0}\nShot speed {:.0}",
+                    ship.max_health(),
+                    ship.mass(),
+                    stats.thruster_force,
+                    stats.projectile_speed,
+                )),
+                TextFont {
+                    font_size: 16.0,
+                    ..default()
+                },
+                TextColor(Color::srgb(0.8, 0.8, 0.9)),
+            ));
+        });
+}
+
+// ship select input, runs every frame while in ship select state
+pub fn handle_ship_select_input(
+    keyboard: Res<ButtonInput<KeyCode>>,
+    cards: Query<(&Interaction, &ShipCard), Changed<Interaction>>,
+    mut next_state: ResMut<NextState<AppState>>,
+    mut message: MessageWriter<PlaySoundEvent>,
+    mut settings: ResMut<PlayerSettings>,
+) {
+    // clicking or tapping a card launches with that ship
+    if let Some((_, card)) = cards
+        .iter()
+        .find(|(interaction, _)| **interaction == Interaction::Pressed)
+    {
+        settings.ship_type = card.0;
+        message.write(PlaySoundEvent::GameStart);
+        next_state.set(AppState::Playing);
+        return;
+    }
+
+    if keyboard.just_pressed(KeyCode::ArrowLeft) || keyboard.just_pressed(KeyCode::KeyA) {
+        settings.ship_type = settings.ship_type.previous();
+        message.write(PlaySoundEvent::MenuBoop);
+    }
+    if keyboard.just_pressed(KeyCode::ArrowRight) || keyboard.just_pressed(KeyCode::KeyD) {
+        settings.ship_type = settings.ship_type.next();
+        message.write(PlaySoundEvent::MenuBoop);
+    }
+
+    if keyboard.just_pressed(KeyCode::Enter) {
+        message.write(PlaySoundEvent::GameStart);
+        next_state.set(AppState::Playing);
+    } else if keyboard.just_pressed(KeyCode::Escape) {
+        message.write(PlaySoundEvent::MenuBoop);
+        next_state.set(AppState::Menu);
+    }
+}
+
+/// Move the highlight to whichever ship is selected
+pub fn update_ship_cards(
+    settings: Res<PlayerSettings>,
+    mut query: Query<(&ShipCard, &mut BorderColor)>,
+) {
+    if !settings.is_changed() {
+        return;
+    }
+
+    for (card, mut border) in query.iter_mut() {
+        *border = BorderColor::all(if card.0 == settings.ship_type {
+            SELECTED_BORDER
+        } else {
+            UNSELECTED_BORDER
+        });
+    }
+}
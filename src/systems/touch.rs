@@ -0,0 +1,200 @@
+//! On-screen controls for touch screens, mainly the web build on phones
+//!
+//! Bevy UI only tracks one pointer, so the buttons are hit-tested by hand against
+//! every finger on the screen. That way both thrusters can be held at once.
+
+use crate::resources::{AppState, PlayState, RunMode, ShipControls, TouchControlsEnabled};
+use bevy::prelude::*;
+use bevy::window::PrimaryWindow;
+
+const BUTTON_COLOR: Color = Color::srgba(1.0, 1.0, 1.0, 0.15);
+const BUTTON_HELD_COLOR: Color = Color::srgba(1.0, 0.8, 0.2, 0.4);
+
+/// One on-screen button
+#[derive(Component, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum TouchButton {
+    TurnLeft,
+    TurnRight,
+    Thrust,
+    Reverse,
+    Fire,
+    SwitchWeapon,
+    Pause,
+}
+
+impl TouchButton {
+    const ALL: [TouchButton; 7] = [
+        TouchButton::TurnLeft,
+        TouchButton::TurnRight,
+        TouchButton::Thrust,
+        TouchButton::Reverse,
+        TouchButton::Fire,
+        TouchButton::SwitchWeapon,
+        TouchButton::Pause,
+    ];
+
+    fn label(&self) -> &'static str {
+        match self {
+            Self::TurnLeft => "<",
+            Self::TurnRight => ">",
+            Self::Thrust => "^",
+            Self::Reverse => "v",
+            Self::Fire => "FIRE",
+            Self::SwitchWeapon => "WPN",
+            Self::Pause => "II",
+        }
+    }
+
+    /// Left, top, width and height as percentages of the window
+    /// Steering sits under the left thumb, reverse and fire under the right
+    fn layout(&self) -> [f32; 4] {
+        match self {
+            Self::TurnLeft => [3.0, 76.0, 14.0, 20.0],
+            Self::TurnRight => [19.0, 76.0, 14.0, 20.0],
+            Self::Thrust => [11.0, 54.0, 14.0, 20.0],
+            Self::Reverse => [65.0, 76.0, 14.0, 20.0],
+            Self::Fire => [81.0, 76.0, 16.0, 20.0],
+            Self::SwitchWeapon => [83.0, 56.0, 12.0, 16.0],
+            Self::Pause => [90.0, 3.0, 7.0, 9.0],
+        }
+    }
+
+    /// Where the button is in logical pixels, the same space touch positions use
+    fn rect(&self, window: &Window) -> Rect {
+        let [left, top, width, height] = self.layout().map(|percent| percent / 100.0);
+        let (w, h) = (window.width(), window.height());
+        Rect::new(left * w, top * h, (left + width) * w, (top + height) * h)
+    }
+}
+
+/// Notice the first touch, runs every frame
+pub fn detect_touch(touches: Res<Touches>, mut enabled: ResMut<TouchControlsEnabled>) {
+    if !enabled.0 && touches.any_just_pressed() {
+        info!("Touch input detected, showing on-screen controls");
+        enabled.0 = true;
+    }
+}
+
+// Spawn the buttons with the run, hidden until a touch screen shows up
+pub fn spawn_touch_controls(mut commands: Commands, enabled: Res<TouchControlsEnabled>) {
+    let visibility = if enabled.0 {
+        Visibility::Visible
+    } else {
+        Visibility::Hidden
+    };
+
+    for button in TouchButton::ALL {
+        let [left, top, width, height] = button.layout();
+        commands
+            .spawn((
+                Node {
+                    position_type: PositionType::Absolute,
+                    left: Val::Percent(left),
+                    top: Val::Percent(top),
+                    width: Val::Percent(width),
+                    height: Val::Percent(height),
+                    justify_content: JustifyContent::Center,
+                    align_items: AlignItems::Center,
+                    ..default()
+                },
+                BackgroundColor(BUTTON_COLOR),
+                visibility,
+                button,
+                DespawnOnExit(AppState::Playing),
+            ))
+            .with_children(|parent| {
+                parent.spawn((
+                    Text::new(button.label()),
+                    TextFont {
+                        font_size: 28.0,
+                        ..default()
+                    },
+                    TextColor(Color::WHITE),
+                ));
+            });
+    }
+}
+
+/// Show the buttons as soon as touch is detected mid-run
+pub fn show_touch_controls(
+    enabled: Res<TouchControlsEnabled>,
+    mut query: Query<&mut Visibility, With<TouchButton>>,
+) {
+    if !enabled.is_changed() || !enabled.0 {
+        return;
+    }
+
+    for mut visibility in query.iter_mut() {
+        *visibility = Visibility::Visible;
+    }
+}
+
+/// Add held buttons to this frame's ShipControls, runs right after read_ship_controls
+///
+/// Rust Concept: Combining input sources with max
+/// The keyboard and gamepad already wrote their strengths, a held button can only add to them
+pub fn read_touch_controls(
+    touches: Res<Touches>,
+    window: Query<&Window, With<PrimaryWindow>>,
+    run_mode: Res<RunMode>,
+    mut controls: ResMut<ShipControls>,
+    mut buttons: Query<(&TouchButton, &mut BackgroundColor)>,
+) {
+    let Ok(window) = window.single() else {
+        return;
+    };
+
+    for (button, mut color) in buttons.iter_mut() {
+        let rect = button.rect(window);
+        let held = touches.iter().any(|touch| rect.contains(touch.position()));
+        *color = BackgroundColor(if held {
+            BUTTON_HELD_COLOR
+        } else {
+            BUTTON_COLOR
+        });
+
+        // a replay is driving the ship
+        if !held || run_mode.is_replay() {
+            continue;
+        }
+        let just_pressed = touches
+            .iter_just_pressed()
+            .any(|touch| rect.contains(touch.position()));
+
+        // same pairing as the keys: turning left fires the right thruster
+        match button {
+            TouchButton::TurnLeft => controls.right_thruster = 1.0,
+            TouchButton::TurnRight => controls.left_thruster = 1.0,
+            TouchButton::Thrust => {
+                controls.left_thruster = 1.0;
+                controls.right_thruster = 1.0;
+            }
+            TouchButton::Reverse => controls.reverse = 1.0,
+            TouchButton::Fire => {
+                controls.fire = true;
+                controls.fire_pressed |= just_pressed;
+            }
+            TouchButton::SwitchWeapon => controls.switch_weapon |= just_pressed,
+            TouchButton::Pause => {}
+        }
+    }
+}
+
+/// The on-screen pause button, the pause menu itself takes taps like clicks
+pub fn touch_pause(
+    touches: Res<Touches>,
+    window: Query<&Window, With<PrimaryWindow>>,
+    mut next_state: ResMut<NextState<PlayState>>,
+) {
+    let Ok(window) = window.single() else {
+        return;
+    };
+
+    let rect = TouchButton::Pause.rect(window);
+    if touches
+        .iter_just_pressed()
+        .any(|touch| rect.contains(touch.position()))
+    {
+        next_state.set(PlayState::Paused);
+    }
+}